@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+
+// Resource limits of the cgroup kifetch runs in (container or systemd slice)
+#[derive(Default)]
+pub struct CgroupLimits {
+    pub memory_limit: Option<usize>,         // Memory limit in bytes
+    pub memory_current: Option<usize>,       // Memory charged to the cgroup, page cache included
+    pub memory_stat: HashMap<String, usize>, // anon, file, shmem and inactive_file in bytes
+    pub cpu_quota: Option<f64>,              // CPU time quota in CPUs (e.g. 1.5)
+    pub cpus: Option<usize>,                 // Number of CPUs in the cpuset
+}
+
+// Directory of our own cgroup for a controller, e.g. /sys/fs/cgroup/kubepods.slice/...
+// along with the mount point of its hierarchy
+#[cfg(target_os = "linux")]
+fn get_cgroup_dir(controller: &str) -> Option<(std::path::PathBuf, std::path::PathBuf)> {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    let cgroups = fs::read_to_string("/proc/self/cgroup").ok()?;
+    let mountinfo = fs::read_to_string("/proc/self/mountinfo").ok()?;
+
+    // "36 32 0:32 / /sys/fs/cgroup/memory rw,relatime - cgroup cgroup rw,memory"
+    let mounts: Vec<(&str, &str, &str, &str)> = mountinfo
+        .lines()
+        .filter_map(|line| {
+            let (left, right) = line.split_once(" - ")?;
+            let left: Vec<&str> = left.split_whitespace().collect();
+            let right: Vec<&str> = right.split_whitespace().collect();
+            Some((
+                *left.get(3)?,
+                *left.get(4)?,
+                *right.first()?,
+                *right.get(2)?,
+            ))
+        })
+        .collect();
+
+    let resolve = |root: &str, mount_point: &str, path: &str| {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        let dir = Path::new(mount_point).join(relative.trim_start_matches('/'));
+
+        // Inside a cgroup namespace the path may not exist below the mount
+        let dir = if dir.exists() {
+            dir
+        } else {
+            PathBuf::from(mount_point)
+        };
+        (dir, PathBuf::from(mount_point))
+    };
+
+    // cgroup v2, when the controller is enabled in the unified hierarchy
+    if let Some(path) = cgroups.lines().find_map(|line| line.strip_prefix("0::")) {
+        if let Some((root, mount_point, _, _)) = mounts.iter().find(|m| m.2 == "cgroup2") {
+            let controllers = fs::read_to_string(Path::new(mount_point).join("cgroup.controllers"))
+                .unwrap_or_default();
+            if controllers.split_whitespace().any(|c| c == controller) {
+                return Some(resolve(root, mount_point, path));
+            }
+        }
+    }
+
+    // cgroup v1, "4:memory:/docker/3f2a..." or "3:cpu,cpuacct:/..."
+    let path = cgroups.lines().find_map(|line| {
+        let mut parts = line.splitn(3, ':');
+        let (_, controllers, path) = (parts.next()?, parts.next()?, parts.next()?);
+        controllers
+            .split(',')
+            .any(|c| c == controller)
+            .then_some(path)
+    })?;
+
+    let (root, mount_point, _, _) = mounts
+        .iter()
+        .find(|m| m.2 == "cgroup" && m.3.split(',').any(|option| option == controller))?;
+
+    Some(resolve(root, mount_point, path))
+}
+
+// Value of a cgroup file in our cgroup or the closest ancestor that has it,
+// limits of every ancestor apply so the smallest one is used
+#[cfg(target_os = "linux")]
+fn get_cgroup_values(controller: &str, file: &str) -> Vec<String> {
+    let (dir, mount_point) = match get_cgroup_dir(controller) {
+        Some(dirs) => dirs,
+        None => return Vec::new(),
+    };
+
+    dir.ancestors()
+        .take_while(|path| path.starts_with(&mount_point))
+        .filter_map(|path| std::fs::read_to_string(path.join(file)).ok())
+        .map(|value| value.trim().to_string())
+        .collect()
+}
+
+// Count the CPUs in a list like "0-3,6,8-9"
+#[cfg(target_os = "linux")]
+fn count_cpus(list: &str) -> usize {
+    list.split(',')
+        .filter_map(|range| match range.split_once('-') {
+            Some((start, end)) => {
+                Some(end.parse::<usize>().ok()? - start.parse::<usize>().ok()? + 1)
+            }
+            None => range.parse::<usize>().ok().map(|_| 1),
+        })
+        .sum()
+}
+
+#[cfg(target_os = "linux")]
+pub fn get_cgroup_limits() -> CgroupLimits {
+    let first_number = |controller: &str, files: &[&str]| {
+        files.iter().find_map(|file| {
+            get_cgroup_values(controller, file)
+                .first()
+                .and_then(|value| value.parse::<usize>().ok())
+        })
+    };
+
+    // v2 "max" means no limit, v1 reports a huge number instead
+    let memory_limit = ["memory.max", "memory.limit_in_bytes"]
+        .iter()
+        .flat_map(|file| get_cgroup_values("memory", file))
+        .filter_map(|value| value.parse::<usize>().ok())
+        .filter(|&limit| limit < (1 << 62))
+        .min();
+
+    // v1 prefixes the hierarchical counters with "total_" and calls anon "rss"
+    let memory_stat = get_cgroup_values("memory", "memory.stat")
+        .first()
+        .map(|stat| {
+            stat.lines()
+                .filter_map(|line| {
+                    let (key, value) = line.split_once(' ')?;
+                    let key = match key {
+                        "anon" | "total_rss" => "anon",
+                        "file" | "total_cache" => "file",
+                        "shmem" | "total_shmem" => "shmem",
+                        "inactive_file" | "total_inactive_file" => "inactive_file",
+                        _ => return None,
+                    };
+                    Some((key.to_string(), value.parse().ok()?))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let memory_current = first_number("memory", &["memory.current", "memory.usage_in_bytes"]);
+
+    // v2 "150000 100000" (quota and period), v1 has them in separate files with -1 for none
+    let cpu_quota = get_cgroup_values("cpu", "cpu.max")
+        .iter()
+        .filter_map(|value| {
+            let (quota, period) = value.split_once(' ')?;
+            Some(quota.parse::<f64>().ok()? / period.parse::<f64>().ok()?)
+        })
+        .chain(
+            get_cgroup_values("cpu", "cpu.cfs_quota_us")
+                .iter()
+                .zip(get_cgroup_values("cpu", "cpu.cfs_period_us").iter())
+                .filter_map(|(quota, period)| {
+                    let quota = quota.parse::<f64>().ok().filter(|&q| q > 0.0)?;
+                    Some(quota / period.parse::<f64>().ok()?)
+                }),
+        )
+        .fold(None, |min: Option<f64>, quota| {
+            Some(min.map_or(quota, |min| min.min(quota)))
+        });
+
+    let cpus = [
+        "cpuset.cpus.effective",
+        "cpuset.effective_cpus",
+        "cpuset.cpus",
+    ]
+    .iter()
+    .find_map(|file| {
+        get_cgroup_values("cpuset", file)
+            .into_iter()
+            .find(|value| !value.is_empty())
+    })
+    .map(|list| count_cpus(&list))
+    .filter(|&count| count > 0);
+
+    CgroupLimits {
+        memory_limit,
+        memory_current,
+        memory_stat,
+        cpu_quota,
+        cpus,
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn get_cgroup_limits() -> CgroupLimits {
+    CgroupLimits::default()
+}
@@ -3,7 +3,6 @@ use std::collections::HashMap;
 use std::error::Error;
 use std::fs;
 use std::path::Path;
-use toml;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Config {
@@ -25,6 +24,108 @@ pub struct GeneralConfig {
 pub struct ModulesConfig {
     pub enabled: Vec<String>,
     pub custom: HashMap<String, String>,
+    #[serde(default)]
+    pub packages: PackagesConfig,
+    #[serde(default)]
+    pub disk: DiskConfig,
+    #[serde(default)]
+    pub storage: StorageConfig,
+    #[serde(default)]
+    pub memory: MemoryConfig,
+    #[serde(default)]
+    pub cpu: CpuConfig,
+    #[serde(default)]
+    pub os: OsConfig,
+    #[serde(default)]
+    pub net: NetConfig,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct PackagesConfig {
+    pub appimage_dirs: Vec<String>,
+    pub languages: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct DiskConfig {
+    pub include_paths: Vec<String>,
+    pub exclude_paths: Vec<String>,
+    pub include_fs_types: Vec<String>,
+    pub exclude_fs_types: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct StorageConfig {
+    pub include_virtual: bool,
+    pub redact_serial: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct CpuConfig {
+    pub cgroup_limits: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct MemoryConfig {
+    pub used: String, // "available", "buffers_cache" or "free"
+    pub cgroup_limits: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct OsConfig {
+    pub machine_id: String, // "hashed", "full" or "hidden"
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct NetConfig {
+    pub throughput_interval: u64, // Milliseconds to sample throughput for, 0 disables it
+    pub exclude_virtual: bool,    // Never pick a VPN, bridge or other virtual interface as primary
+}
+
+impl Default for PackagesConfig {
+    fn default() -> Self {
+        PackagesConfig {
+            appimage_dirs: vec![
+                "~/Applications".to_string(),
+                "~/AppImages".to_string(),
+                "~/.local/bin".to_string(),
+            ],
+            languages: Vec::new(),
+        }
+    }
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        StorageConfig {
+            include_virtual: false,
+            redact_serial: true,
+        }
+    }
+}
+
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        MemoryConfig {
+            used: "available".to_string(),
+            cgroup_limits: false,
+        }
+    }
+}
+
+impl Default for OsConfig {
+    fn default() -> Self {
+        OsConfig {
+            machine_id: "hashed".to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -52,7 +153,7 @@ impl Config {
                 padding: 2,
             },
             colors: [
-                ("color_1".to_string(), "bright_red".to_string()),
+                ("color_1".to_string(), "os".to_string()),
                 ("color_2".to_string(), "bright_green".to_string()),
                 ("color_3".to_string(), "bright_yellow".to_string()),
                 ("color_4".to_string(), "bright_blue".to_string()),
@@ -67,6 +168,7 @@ impl Config {
                 enabled: vec![
                     "os".to_string(),
                     "kernel".to_string(),
+                    "host".to_string(),
                     "uptime".to_string(),
                     "packages".to_string(),
                     "shell".to_string(),
@@ -75,12 +177,19 @@ impl Config {
                     "memory".to_string(),
                 ],
                 custom: HashMap::new(),
+                packages: PackagesConfig::default(),
+                disk: DiskConfig::default(),
+                storage: StorageConfig::default(),
+                memory: MemoryConfig::default(),
+                cpu: CpuConfig::default(),
+                os: OsConfig::default(),
+                net: NetConfig::default(),
             },
             layout: LayoutConfig {
                 lines: vec![
                     "{color_1}{os_name}{color_reset}".to_string(),
                     "{color_2}Kernel{color_reset}{separator}{kernel_version}".to_string(),
-                    "{color_3}Host{color_reset}{separator}{os_hostname}".to_string(),
+                    "{color_3}Host{color_reset}{separator}{host_vendor} {host_model}".to_string(),
                     "{color_4}Uptime{color_reset}{separator}{uptime}".to_string(),
                     "{color_5}CPU{color_reset}{separator}{cpu_model}".to_string(),
                     "{color_6}Memory{color_reset}{separator}{memory_used}/{memory_total}"
@@ -90,6 +199,7 @@ impl Config {
         }
     }
 
+    #[allow(dead_code)]
     pub fn save(&self, path: &str) -> Result<(), Box<dyn Error>> {
         let content = toml::to_string_pretty(self)?;
 
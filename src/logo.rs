@@ -30,8 +30,7 @@ fn normalize_logo(logo: &str) -> Vec<String> {
         .unwrap_or(0);
 
     // Normalize each line
-    for i in start..end {
-        let line = lines[i];
+    for line in &lines[start..end] {
         // Add spaces to the right of the line
         let padding = " ".repeat(max_width - line.len());
         result.push(format!("{}{}", line, padding));
@@ -1,5 +1,6 @@
 use std::error::Error;
 
+mod cgroup;
 mod config;
 mod constants;
 mod logo;
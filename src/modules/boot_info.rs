@@ -0,0 +1,224 @@
+use super::SystemInfo;
+
+struct BootInfo {
+    firmware: String,    // UEFI or BIOS
+    secure_boot: String, // Enabled, Disabled, Setup mode or N/A
+    loader: String,      // Bootloader (e.g. systemd-boot 256.4, GRUB)
+    cmdline: String,     // Kernel command line
+    timestamp: u64,      // Boot time in seconds since the Unix epoch
+    time: String,        // Boot time as "YYYY-MM-DD HH:MM"
+}
+
+impl Default for BootInfo {
+    fn default() -> Self {
+        BootInfo {
+            firmware: "Unknown".to_string(),
+            secure_boot: "N/A".to_string(),
+            loader: "Unknown".to_string(),
+            cmdline: "N/A".to_string(),
+            timestamp: 0,
+            time: "Unknown".to_string(),
+        }
+    }
+}
+
+// EFI variables, named <name>-<vendor guid>
+#[cfg(target_os = "linux")]
+const EFI_GLOBAL_VARIABLE: &str = "8be4df61-93ca-11d2-aa0d-00e098032b8c";
+#[cfg(target_os = "linux")]
+const LOADER_VARIABLE: &str = "4a67b082-0a4c-41cf-b6c7-440b29bb8c4f"; // Boot Loader Interface
+
+// Bootloaders recognizable by their files, checked in order
+#[cfg(target_os = "linux")]
+const LOADER_FILES: &[(&str, &str)] = &[
+    ("/boot/limine.conf", "Limine"),
+    ("/boot/limine", "Limine"),
+    ("/boot/EFI/limine", "Limine"),
+    // Not loader/entries, GRUB reads those too on Fedora (BootLoaderSpec)
+    ("/boot/loader/loader.conf", "systemd-boot"),
+    ("/efi/loader/loader.conf", "systemd-boot"),
+    ("/boot/efi/loader/loader.conf", "systemd-boot"),
+    ("/boot/EFI/systemd", "systemd-boot"),
+    ("/efi/EFI/systemd", "systemd-boot"),
+    ("/boot/efi/EFI/systemd", "systemd-boot"),
+    ("/boot/refind_linux.conf", "rEFInd"),
+    ("/boot/EFI/refind", "rEFInd"),
+    ("/boot/grub/grub.cfg", "GRUB"),
+    ("/boot/grub2/grub.cfg", "GRUB"),
+    ("/boot/syslinux", "Syslinux"),
+    ("/boot/extlinux", "Syslinux"),
+];
+
+// Boot time in local time, e.g. "2026-10-17 08:12"
+#[cfg(target_os = "linux")]
+fn format_time(timestamp: u64) -> String {
+    let time = timestamp as libc::time_t;
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+
+    if unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+        return "Unknown".to_string();
+    }
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min
+    )
+}
+
+// Read an EFI variable, skipping its 4 byte attribute header
+#[cfg(target_os = "linux")]
+fn read_efi_variable(name: &str, vendor: &str) -> Option<Vec<u8>> {
+    let data = std::fs::read(format!("/sys/firmware/efi/efivars/{}-{}", name, vendor)).ok()?;
+    data.get(4..).map(|value| value.to_vec())
+}
+
+#[cfg(target_os = "linux")]
+fn get_boot() -> BootInfo {
+    use std::fs;
+    use std::path::Path;
+
+    let mut boot = BootInfo::default();
+
+    if Path::new("/sys/firmware/efi").exists() {
+        boot.firmware = "UEFI".to_string();
+
+        let flag = |name: &str| {
+            read_efi_variable(name, EFI_GLOBAL_VARIABLE).and_then(|value| value.first().copied())
+        };
+        boot.secure_boot = match (flag("SecureBoot"), flag("SetupMode")) {
+            (_, Some(1)) => "Setup mode".to_string(),
+            (Some(1), _) => "Enabled".to_string(),
+            (Some(_), _) => "Disabled".to_string(),
+            _ => "Unknown".to_string(),
+        };
+
+        // systemd-boot and Limine announce themselves, e.g. "systemd-boot 256.4" (UTF-16)
+        if let Some(value) = read_efi_variable("LoaderInfo", LOADER_VARIABLE) {
+            let chars: Vec<u16> = value
+                .chunks_exact(2)
+                .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                .take_while(|&c| c != 0)
+                .collect();
+            let loader = String::from_utf16_lossy(&chars).trim().to_string();
+            if !loader.is_empty() {
+                boot.loader = loader;
+            }
+        }
+    } else {
+        boot.firmware = "BIOS".to_string();
+    }
+
+    if boot.loader == "Unknown" {
+        if let Some((_, loader)) = LOADER_FILES
+            .iter()
+            .find(|(path, _)| Path::new(path).exists())
+        {
+            boot.loader = loader.to_string();
+        }
+    }
+
+    if let Ok(cmdline) = fs::read_to_string("/proc/cmdline") {
+        boot.cmdline = cmdline.trim().to_string();
+    }
+
+    // "btime 1792218720"
+    if let Some(btime) = fs::read_to_string("/proc/stat").ok().and_then(|stat| {
+        stat.lines()
+            .find_map(|line| line.strip_prefix("btime "))
+            .and_then(|btime| btime.trim().parse::<u64>().ok())
+    }) {
+        boot.timestamp = btime;
+        boot.time = format_time(btime);
+    }
+
+    boot
+}
+
+// Boot time in UTC, e.g. "2026-10-17 08:12 UTC"
+#[cfg(target_os = "windows")]
+fn format_time(timestamp: u64) -> String {
+    let days = (timestamp / 86400) as i64;
+    let seconds = timestamp % 86400;
+
+    // Days since the epoch to a civil date (Howard Hinnant's algorithm)
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60
+    )
+}
+
+#[cfg(target_os = "windows")]
+fn get_boot() -> BootInfo {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    use windows::Win32::System::SystemInformation::{
+        FirmwareTypeUefi, GetFirmwareType, GetTickCount64, FIRMWARE_TYPE,
+    };
+    use winreg::{enums::HKEY_LOCAL_MACHINE, RegKey};
+
+    let mut boot = BootInfo {
+        loader: "Windows Boot Manager".to_string(),
+        ..Default::default()
+    };
+
+    let mut firmware = FIRMWARE_TYPE::default();
+    if unsafe { GetFirmwareType(&mut firmware) }.is_ok() {
+        boot.firmware = if firmware == FirmwareTypeUefi {
+            "UEFI".to_string()
+        } else {
+            "BIOS".to_string()
+        };
+    }
+
+    if boot.firmware == "UEFI" {
+        let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+        boot.secure_boot = match hklm
+            .open_subkey("SYSTEM\\CurrentControlSet\\Control\\SecureBoot\\State")
+            .and_then(|key| key.get_value::<u32, _>("UEFISecureBootEnabled"))
+        {
+            Ok(1) => "Enabled".to_string(),
+            Ok(_) => "Disabled".to_string(),
+            Err(_) => "Unknown".to_string(),
+        };
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_secs())
+        .unwrap_or(0);
+    let uptime = unsafe { GetTickCount64() } / 1000;
+
+    boot.timestamp = now.saturating_sub(uptime);
+    boot.time = format_time(boot.timestamp);
+
+    boot
+}
+
+pub fn load_boot_info(info: &mut SystemInfo) {
+    let boot = get_boot();
+
+    info.insert("boot_firmware".to_string(), boot.firmware);
+    info.insert("boot_secure_boot".to_string(), boot.secure_boot);
+    info.insert("boot_loader".to_string(), boot.loader);
+    info.insert("boot_cmdline".to_string(), boot.cmdline);
+    info.insert("boot_timestamp".to_string(), boot.timestamp.to_string());
+    info.insert("boot_time".to_string(), boot.time);
+}
@@ -1,46 +1,260 @@
 use super::SystemInfo;
+use crate::cgroup::get_cgroup_limits;
+use crate::config::CpuConfig;
 
+// x86 vendor_id strings
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+const X86_VENDORS: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "GenuineIntel" => "Intel",
+    "AuthenticAMD" => "AMD",
+    "HygonGenuine" => "Hygon",
+    "CentaurHauls" => "Centaur",
+    "Shanghai" => "Zhaoxin",
+    "VIA VIA VIA" => "VIA",
+};
+
+// ARM "CPU implementer" codes
+#[cfg(target_os = "linux")]
+const ARM_IMPLEMENTERS: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "0x41" => "ARM",
+    "0x42" => "Broadcom",
+    "0x43" => "Cavium",
+    "0x46" => "Fujitsu",
+    "0x48" => "HiSilicon",
+    "0x4e" => "NVIDIA",
+    "0x50" => "APM",
+    "0x51" => "Qualcomm",
+    "0x53" => "Samsung",
+    "0x61" => "Apple",
+    "0x6d" => "Microsoft",
+    "0xc0" => "Ampere",
+};
+
+// ARM "CPU implementer:CPU part" codes
+#[cfg(target_os = "linux")]
+const ARM_PARTS: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "0x41:0xb76" => "ARM1176",
+    "0x41:0xc07" => "Cortex-A7",
+    "0x41:0xc0f" => "Cortex-A15",
+    "0x41:0xd03" => "Cortex-A53",
+    "0x41:0xd04" => "Cortex-A35",
+    "0x41:0xd05" => "Cortex-A55",
+    "0x41:0xd07" => "Cortex-A57",
+    "0x41:0xd08" => "Cortex-A72",
+    "0x41:0xd09" => "Cortex-A73",
+    "0x41:0xd0a" => "Cortex-A75",
+    "0x41:0xd0b" => "Cortex-A76",
+    "0x41:0xd0c" => "Neoverse-N1",
+    "0x41:0xd0d" => "Cortex-A77",
+    "0x41:0xd40" => "Neoverse-V1",
+    "0x41:0xd41" => "Cortex-A78",
+    "0x41:0xd44" => "Cortex-X1",
+    "0x41:0xd46" => "Cortex-A510",
+    "0x41:0xd47" => "Cortex-A710",
+    "0x41:0xd48" => "Cortex-X2",
+    "0x41:0xd49" => "Neoverse-N2",
+    "0x41:0xd4d" => "Cortex-A715",
+    "0x41:0xd4e" => "Cortex-X3",
+    "0x41:0xd4f" => "Neoverse-V2",
+    "0x41:0xd80" => "Cortex-A520",
+    "0x41:0xd81" => "Cortex-A720",
+    "0x41:0xd82" => "Cortex-X4",
+    "0x41:0xd84" => "Neoverse-V3",
+    "0x41:0xd8e" => "Neoverse-N3",
+    "0x43:0x0af" => "ThunderX2",
+    "0x46:0x001" => "A64FX",
+    "0x48:0xd01" => "Kunpeng-920",
+    "0x4e:0x004" => "Carmel",
+    "0x51:0x001" => "Oryon",
+    "0x61:0x022" => "M1 Icestorm",
+    "0x61:0x023" => "M1 Firestorm",
+    "0xc0:0xac3" => "Ampere-1",
+    "0xc0:0xac4" => "Ampere-1a",
+};
+
+// RISC-V "uarch" vendor prefixes
+#[cfg(target_os = "linux")]
+const RISCV_VENDORS: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "sifive" => "SiFive",
+    "thead" => "T-Head",
+    "andestech" => "Andes",
+    "spacemit" => "SpacemiT",
+    "starfive" => "StarFive",
+};
+
+// Curated instruction set extensions (flag, label) from the x86 "flags" line
+#[cfg(target_os = "linux")]
+const X86_FEATURES: &[(&str, &str)] = &[
+    ("sse4_2", "SSE4.2"),
+    ("avx", "AVX"),
+    ("avx2", "AVX2"),
+    ("avx512f", "AVX-512"),
+    ("fma", "FMA"),
+    ("aes", "AES-NI"),
+    ("sha_ni", "SHA"),
+];
+
+// Curated instruction set extensions (flag, label) from the ARM "Features" line
+#[cfg(target_os = "linux")]
+const ARM_FEATURES: &[(&str, &str)] = &[
+    ("asimd", "NEON"),
+    ("neon", "NEON"),
+    ("sve", "SVE"),
+    ("sve2", "SVE2"),
+    ("aes", "AES"),
+    ("sha2", "SHA2"),
+    ("crc32", "CRC32"),
+    ("atomics", "LSE"),
+];
+
+#[derive(Default)]
 struct CpuInfo {
     pub model: String,
     pub cores: usize,
     pub threads: usize,
     pub freq_mhz: String,
     pub freq_ghz: String,
+    pub vendor: String,
+    pub family: String,
+    pub model_id: String,
+    pub stepping: String,
+    pub microcode: String,
+    pub isa: String,
+    pub features: Vec<String>,
+}
+
+// Pick the labels of the curated extensions present in a flags line
+#[cfg(target_os = "linux")]
+fn get_features(flags: &str, curated: &[(&str, &str)]) -> Vec<String> {
+    let flags: Vec<&str> = flags.split_whitespace().collect();
+    let mut features: Vec<String> = Vec::new();
+
+    for (flag, label) in curated {
+        if flags.contains(flag) && !features.iter().any(|f| f == label) {
+            features.push(label.to_string());
+        }
+    }
+
+    features
 }
 
-impl Default for CpuInfo {
-    fn default() -> Self {
-        CpuInfo {
-            model: String::new(),
-            cores: 0,
-            threads: 0,
-            freq_mhz: String::new(),
-            freq_ghz: String::new(),
+// Cores and threads in /proc/cpuinfo. Every logical processor has its own block,
+// cores are unique (physical id, core id) pairs, so SMT siblings and the same core
+// id on another socket are told apart
+#[cfg(target_os = "linux")]
+fn count_cores(cpu_info: &str) -> (usize, usize) {
+    let threads = cpu_info
+        .lines()
+        .filter(|line| line.starts_with("processor"))
+        .count();
+
+    let mut cores: Vec<(String, String)> = Vec::new();
+    for block in cpu_info.split("\n\n") {
+        let value = |key: &str| {
+            block.lines().find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                (name.trim() == key).then(|| value.trim().to_string())
+            })
+        };
+        if let (Some(package), Some(core)) = (value("physical id"), value("core id")) {
+            if !cores.contains(&(package.clone(), core.clone())) {
+                cores.push((package, core));
+            }
         }
     }
+
+    // ARM and RISC-V list no topology, each processor is a core
+    match cores.is_empty() {
+        true => (threads, threads),
+        false => (cores.len(), threads),
+    }
 }
 
 #[cfg(target_os = "linux")]
 fn get_cpu() -> CpuInfo {
+    use std::collections::HashMap;
     use std::fs;
 
     let mut info = CpuInfo::default();
 
     if let Ok(cpu_info) = fs::read_to_string("/proc/cpuinfo") {
+        // Keep the first value of every field (first processor block)
+        let mut fields: HashMap<&str, &str> = HashMap::new();
         for line in cpu_info.lines() {
-            if line.starts_with("model name") {
-                if let Some(model) = line.split(':').nth(1) {
-                    info.model = model.trim().to_string();
-                    break;
+            if let Some((key, value)) = line.split_once(':') {
+                fields.entry(key.trim()).or_insert(value.trim());
+            }
+        }
+
+        let field = |key: &str| fields.get(key).map(|v| v.to_string()).unwrap_or_default();
+
+        info.model = field("model name");
+        info.family = field("cpu family");
+        info.model_id = field("model");
+        info.stepping = field("stepping");
+        info.microcode = field("microcode");
+
+        if let Some(flags) = fields.get("flags") {
+            // x86
+            let vendor_id = field("vendor_id");
+            info.vendor = X86_VENDORS
+                .get(vendor_id.as_str())
+                .map(|v| v.to_string())
+                .unwrap_or(vendor_id);
+            info.features = get_features(flags, X86_FEATURES);
+        } else if let Some(implementer) = fields.get("CPU implementer") {
+            // ARM
+            let implementer = implementer.to_lowercase();
+            let part = field("CPU part").to_lowercase();
+            info.vendor = ARM_IMPLEMENTERS
+                .get(implementer.as_str())
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| implementer.clone());
+            info.family = field("CPU architecture");
+            info.model_id = part.clone();
+
+            // "CPU variant : 0x1" and "CPU revision : 2" -> r1p2
+            let variant =
+                u32::from_str_radix(field("CPU variant").trim_start_matches("0x"), 16).ok();
+            let revision = field("CPU revision").parse::<u32>().ok();
+            info.stepping = match (variant, revision) {
+                (Some(variant), Some(revision)) => format!("r{}p{}", variant, revision),
+                _ => String::new(),
+            };
+
+            if info.model.is_empty() {
+                let key = format!("{}:{}", implementer, part);
+                info.model = match ARM_PARTS.get(key.as_str()) {
+                    Some(name) => format!("{} {}", info.vendor, name),
+                    None => format!("{} {}", info.vendor, part),
+                };
+            }
+
+            info.features = get_features(&field("Features"), ARM_FEATURES);
+        } else if let Some(isa) = fields.get("isa") {
+            // RISC-V, e.g. "uarch : sifive,u74-mc"
+            info.isa = isa.to_string();
+            let uarch = field("uarch");
+            if let Some((vendor, core)) = uarch.split_once(',') {
+                info.vendor = RISCV_VENDORS
+                    .get(vendor)
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|| vendor.to_string());
+
+                if info.model.is_empty() {
+                    info.model = format!("{} {}", info.vendor, core.to_uppercase());
                 }
+            } else if info.model.is_empty() {
+                info.model = uarch;
             }
         }
 
-        // Get number of cores
-        info.cores = cpu_info
-            .lines()
-            .filter(|line| line.starts_with("processor"))
-            .count();
+        // Some boards only report the SoC name
+        if info.model.is_empty() {
+            info.model = field("Hardware");
+        }
+
+        (info.cores, info.threads) = count_cores(&cpu_info);
     }
 
     // Get CPU frequency
@@ -54,7 +268,7 @@ fn get_cpu() -> CpuInfo {
         }
     }
 
-    return info;
+    info
 }
 
 #[cfg(target_os = "windows")]
@@ -148,17 +362,87 @@ fn get_cpu() -> CpuInfo {
                 info.freq_mhz = format!("{}", freq_mhz);
                 info.freq_ghz = format!("{:.2}", freq_mhz as f64 / 1000.0);
             }
+
+            // Read vendor, e.g. "GenuineIntel"
+            let vendor_value: Result<String, _> = cpu_key.get_value("VendorIdentifier");
+            if let Ok(vendor_id) = vendor_value {
+                info.vendor = X86_VENDORS
+                    .get(vendor_id.as_str())
+                    .map(|v| v.to_string())
+                    .unwrap_or(vendor_id);
+            }
+
+            // Read identifier, e.g. "Intel64 Family 6 Model 158 Stepping 10"
+            let id_value: Result<String, _> = cpu_key.get_value("Identifier");
+            if let Ok(identifier) = id_value {
+                let parts: Vec<&str> = identifier.split_whitespace().collect();
+                for pair in parts.windows(2) {
+                    match pair[0] {
+                        "Family" => info.family = pair[1].to_string(),
+                        "Model" => info.model_id = pair[1].to_string(),
+                        "Stepping" => info.stepping = pair[1].to_string(),
+                        _ => {}
+                    }
+                }
+            }
         }
     }
 
-    return info;
+    info
 }
 
-pub fn load_cpu_info(info: &mut SystemInfo) {
+pub fn load_cpu_info(config: &CpuConfig, info: &mut SystemInfo) {
     let cpu = get_cpu();
+    let limits = get_cgroup_limits();
+
+    // CPUs the cpuset allows, further capped by the CPU time quota
+    let mut effective = limits.cpus.unwrap_or(cpu.threads).min(cpu.threads);
+    if let Some(quota) = limits.cpu_quota {
+        effective = effective.min((quota.ceil() as usize).max(1));
+    }
+
+    let (cores, threads) = if config.cgroup_limits {
+        (cpu.cores.min(effective), effective)
+    } else {
+        (cpu.cores, cpu.threads)
+    };
+
     info.insert("cpu_model".to_string(), cpu.model);
-    info.insert("cpu_cores".to_string(), cpu.cores.to_string());
-    info.insert("cpu_threads".to_string(), cpu.threads.to_string());
+    info.insert("cpu_cores".to_string(), cores.to_string());
+    info.insert("cpu_threads".to_string(), threads.to_string());
+    info.insert(
+        "cpu_quota".to_string(),
+        limits
+            .cpu_quota
+            .map(|quota| format!("{:.2}", quota))
+            .unwrap_or_else(|| "None".to_string()),
+    );
+    info.insert("cpu_effective_count".to_string(), effective.to_string());
     info.insert("cpu_freq_mhz".to_string(), cpu.freq_mhz);
     info.insert("cpu_freq_ghz".to_string(), cpu.freq_ghz);
+    info.insert("cpu_vendor".to_string(), cpu.vendor);
+    info.insert("cpu_family".to_string(), cpu.family);
+    info.insert("cpu_model_id".to_string(), cpu.model_id);
+    info.insert("cpu_stepping".to_string(), cpu.stepping);
+    info.insert("cpu_microcode".to_string(), cpu.microcode);
+    info.insert("cpu_isa".to_string(), cpu.isa);
+    info.insert("cpu_features".to_string(), cpu.features.join(", "));
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    // Two sockets of two cores with two threads each, core ids repeat on each socket
+    #[test]
+    fn dual_socket_cores() {
+        let cpu_info = include_str!("../../tests/fixtures/cpuinfo-dual-socket");
+        assert_eq!(count_cores(cpu_info), (4, 8));
+    }
+
+    #[test]
+    fn cores_without_topology() {
+        let cpu_info = "processor\t: 0\nBogoMIPS\t: 48.00\n\nprocessor\t: 1\nBogoMIPS\t: 48.00\n";
+        assert_eq!(count_cores(cpu_info), (2, 2));
+    }
 }
@@ -0,0 +1,259 @@
+use super::SystemInfo;
+
+// Maximum time to wait for a desktop binary to print its version
+#[cfg(target_os = "linux")]
+const VERSION_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(500);
+
+struct DesktopInfo {
+    name: String,    // Desktop environment (e.g. GNOME, KDE Plasma)
+    version: String, // Desktop environment version
+    wm: String,      // Window manager or Wayland compositor
+    session: String, // Session type (x11, wayland or tty)
+}
+
+impl Default for DesktopInfo {
+    fn default() -> Self {
+        DesktopInfo {
+            name: "Unknown".to_string(),
+            version: "Unknown".to_string(),
+            wm: "Unknown".to_string(),
+            session: "Unknown".to_string(),
+        }
+    }
+}
+
+// XDG_CURRENT_DESKTOP values
+#[cfg(target_os = "linux")]
+const DESKTOPS: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "gnome" => "GNOME",
+    "kde" => "KDE Plasma",
+    "xfce" => "Xfce",
+    "x-cinnamon" => "Cinnamon",
+    "cinnamon" => "Cinnamon",
+    "mate" => "MATE",
+    "lxqt" => "LXQt",
+    "lxde" => "LXDE",
+    "budgie" => "Budgie",
+    "pantheon" => "Pantheon",
+    "deepin" => "Deepin",
+    "unity" => "Unity",
+    "enlightenment" => "Enlightenment",
+    "cosmic" => "COSMIC",
+    "trinity" => "Trinity",
+};
+
+// Processes that identify a desktop environment
+#[cfg(target_os = "linux")]
+const DESKTOP_PROCESSES: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "gnome-shell" => "GNOME",
+    "plasmashell" => "KDE Plasma",
+    "xfce4-session" => "Xfce",
+    "cinnamon" => "Cinnamon",
+    "mate-session" => "MATE",
+    "lxqt-session" => "LXQt",
+    "lxsession" => "LXDE",
+    "budgie-panel" => "Budgie",
+    "gala" => "Pantheon",
+    "cosmic-session" => "COSMIC",
+};
+
+// Window managers and compositors by process name (/proc/<pid>/comm)
+#[cfg(target_os = "linux")]
+const WINDOW_MANAGERS: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "gnome-shell" => "Mutter",
+    "mutter" => "Mutter",
+    "kwin_x11" => "KWin",
+    "kwin_wayland" => "KWin",
+    "xfwm4" => "Xfwm4",
+    "marco" => "Marco",
+    "cinnamon" => "Muffin",
+    "muffin" => "Muffin",
+    "openbox" => "Openbox",
+    "fluxbox" => "Fluxbox",
+    "icewm" => "IceWM",
+    "jwm" => "JWM",
+    "fvwm" => "FVWM",
+    "fvwm3" => "FVWM",
+    "enlightenment" => "Enlightenment",
+    "compiz" => "Compiz",
+    "i3" => "i3",
+    "bspwm" => "bspwm",
+    "awesome" => "awesome",
+    "dwm" => "dwm",
+    "herbstluftwm" => "herbstluftwm",
+    "spectrwm" => "spectrwm",
+    "leftwm" => "LeftWM",
+    "qtile" => "Qtile",
+    "xmonad" => "xmonad",
+    "xmonad-x86_64-l" => "xmonad",
+    "sway" => "Sway",
+    "Hyprland" => "Hyprland",
+    "river" => "river",
+    "wayfire" => "Wayfire",
+    "labwc" => "labwc",
+    "niri" => "niri",
+    "weston" => "Weston",
+    "cage" => "Cage",
+    "dwl" => "dwl",
+    "gamescope" => "Gamescope",
+    "cosmic-comp" => "cosmic-comp",
+    "gala" => "Gala",
+    "budgie-wm" => "Budgie WM",
+};
+
+// Names of all running processes
+#[cfg(target_os = "linux")]
+fn get_process_names() -> Vec<String> {
+    use std::fs;
+
+    let mut names = Vec::new();
+
+    if let Ok(entries) = fs::read_dir("/proc") {
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            if !name.to_string_lossy().chars().all(|c| c.is_ascii_digit()) {
+                continue;
+            }
+
+            if let Ok(comm) = fs::read_to_string(entry.path().join("comm")) {
+                names.push(comm.trim().to_string());
+            }
+        }
+    }
+
+    names
+}
+
+#[cfg(target_os = "linux")]
+fn get_desktop_version(name: &str) -> Option<String> {
+    use crate::utils::{parse_version, run_with_timeout};
+    use std::fs;
+
+    match name {
+        "GNOME" => {
+            // <platform>46</platform> <minor>0</minor>
+            if let Ok(xml) = fs::read_to_string("/usr/share/gnome/gnome-version.xml") {
+                let tag = |tag: &str| {
+                    let start = xml.find(&format!("<{}>", tag))? + tag.len() + 2;
+                    let end = xml[start..].find('<')? + start;
+                    Some(xml[start..end].trim().to_string())
+                };
+
+                if let (Some(platform), Some(minor)) = (tag("platform"), tag("minor")) {
+                    return Some(format!("{}.{}", platform, minor));
+                }
+            }
+        }
+        "KDE Plasma" => {
+            let sessions = [
+                "/usr/share/wayland-sessions/plasma.desktop",
+                "/usr/share/xsessions/plasma.desktop",
+                "/usr/share/xsessions/plasmax11.desktop",
+            ];
+
+            for session in sessions {
+                if let Ok(content) = fs::read_to_string(session) {
+                    for line in content.lines() {
+                        if let Some(version) = line.strip_prefix("X-KDE-PluginInfo-Version=") {
+                            return Some(version.trim().to_string());
+                        }
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+
+    // Fall back to asking the desktop's main binary
+    let binary = match name {
+        "GNOME" => "gnome-shell",
+        "KDE Plasma" => "plasmashell",
+        "Xfce" => "xfce4-session",
+        "Cinnamon" => "cinnamon",
+        "MATE" => "mate-session",
+        "LXQt" => "lxqt-session",
+        "Budgie" => "budgie-desktop",
+        _ => return None,
+    };
+
+    run_with_timeout(binary, &["--version"], VERSION_TIMEOUT).and_then(|out| parse_version(&out))
+}
+
+#[cfg(target_os = "linux")]
+fn get_desktop() -> DesktopInfo {
+    use std::env;
+
+    let mut desktop = DesktopInfo::default();
+    let processes = get_process_names();
+
+    // Session type
+    desktop.session = match env::var("XDG_SESSION_TYPE") {
+        Ok(session) if !session.is_empty() => session,
+        _ if env::var("WAYLAND_DISPLAY").is_ok() => "wayland".to_string(),
+        _ if env::var("DISPLAY").is_ok() => "x11".to_string(),
+        _ => "tty".to_string(),
+    };
+
+    // Window manager or compositor
+    if let Some(wm) = processes
+        .iter()
+        .find_map(|process| WINDOW_MANAGERS.get(process.as_str()))
+    {
+        desktop.wm = wm.to_string();
+    }
+
+    // Desktop environment, e.g. XDG_CURRENT_DESKTOP="ubuntu:GNOME"
+    let current = env::var("XDG_CURRENT_DESKTOP")
+        .or_else(|_| env::var("XDG_SESSION_DESKTOP"))
+        .or_else(|_| env::var("DESKTOP_SESSION"))
+        .unwrap_or_default();
+
+    let known = current
+        .split(':')
+        .find_map(|part| DESKTOPS.get(part.to_lowercase().as_str()));
+
+    if let Some(name) = known {
+        desktop.name = name.to_string();
+    } else if let Some(name) = processes
+        .iter()
+        .find_map(|process| DESKTOP_PROCESSES.get(process.as_str()))
+    {
+        desktop.name = name.to_string();
+    } else if let Some(part) = current.split(':').next_back().filter(|p| !p.is_empty()) {
+        // Standalone window managers (e.g. "Hyprland") are not desktops
+        if !part.eq_ignore_ascii_case(&desktop.wm) {
+            desktop.name = part.to_string();
+        }
+    }
+
+    if let Some(version) = get_desktop_version(&desktop.name) {
+        desktop.version = version;
+    }
+
+    desktop
+}
+
+#[cfg(target_os = "windows")]
+fn get_desktop() -> DesktopInfo {
+    DesktopInfo {
+        wm: "DWM".to_string(),
+        ..Default::default()
+    }
+}
+
+pub fn load_desktop_info(info: &mut SystemInfo) {
+    let desktop = get_desktop();
+
+    // Summary, e.g. "KDE Plasma 6.1.3 (KWin, wayland)"
+    let mut summary = desktop.name.clone();
+    if desktop.version != "Unknown" {
+        summary.push_str(&format!(" {}", desktop.version));
+    }
+    summary.push_str(&format!(" ({}, {})", desktop.wm, desktop.session));
+
+    info.insert("desktop".to_string(), summary);
+    info.insert("desktop_name".to_string(), desktop.name);
+    info.insert("desktop_version".to_string(), desktop.version);
+    info.insert("desktop_wm".to_string(), desktop.wm);
+    info.insert("desktop_session".to_string(), desktop.session);
+}
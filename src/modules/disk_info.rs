@@ -1,49 +1,100 @@
 use super::SystemInfo;
+use crate::config::DiskConfig;
 use crate::utils::insert_size;
 
 /// Represents disk usage information with used, free and total space in bytes
+#[derive(Default)]
 struct DiskInfo {
-    used: usize,  // Space used in bytes
-    free: usize,  // Free space available in bytes
-    total: usize, // Total disk capacity in bytes
+    mount: String,   // Mount point (drive letter on Windows)
+    device: String,  // Source device
+    fs_type: String, // Filesystem type
+    used: usize,     // Space used in bytes
+    free: usize,     // Free space available in bytes
+    total: usize,    // Total disk capacity in bytes
 }
 
-impl Default for DiskInfo {
-    fn default() -> DiskInfo {
-        DiskInfo {
-            used: 0,
-            free: 0,
-            total: 0,
+// Filesystem types to ignore (virtual/special filesystems)
+#[cfg(target_os = "linux")]
+const FS_TYPES_TO_IGNORE: &[&str] = &[
+    "sysfs",
+    "proc",
+    "devpts",
+    "tmpfs",
+    "devtmpfs",
+    "debugfs",
+    "securityfs",
+    "cgroup",
+    "cgroup2",
+    "pstore",
+    "autofs",
+    "mqueue",
+    "hugetlbfs",
+    "fusectl",
+    "fuse.gvfsd-fuse",
+    "tracefs",
+    "configfs",
+    "binfmt_misc",
+    "bpf",
+    "efivarfs",
+    "nsfs",
+    "ramfs",
+    "rpc_pipefs",
+    "squashfs",
+];
+
+// /proc/mounts escapes spaces and tabs as octal, e.g. "/mnt/my\040disk"
+#[cfg(target_os = "linux")]
+fn unescape_mount(path: &str) -> String {
+    let mut result = String::new();
+    let mut chars = path.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            let code: String = chars.by_ref().take(3).collect();
+            if let Ok(byte) = u8::from_str_radix(&code, 8) {
+                result.push(byte as char);
+                continue;
+            }
+            result.push(c);
+            result.push_str(&code);
+        } else {
+            result.push(c);
         }
     }
+
+    result
+}
+
+// Included paths must match exactly, excluded paths also exclude everything below them
+fn is_mount_selected(config: &DiskConfig, mount: &str, fs_type: &str) -> bool {
+    let below = |path: &String| {
+        let path = path.trim_end_matches('/');
+        mount == path || mount.starts_with(&format!("{}/", path))
+    };
+
+    if !config.include_paths.is_empty() && !config.include_paths.iter().any(|p| p == mount) {
+        return false;
+    }
+
+    if !config.include_fs_types.is_empty() && !config.include_fs_types.iter().any(|t| t == fs_type)
+    {
+        return false;
+    }
+
+    !config.exclude_paths.iter().any(below) && !config.exclude_fs_types.iter().any(|t| t == fs_type)
 }
 
 #[cfg(target_os = "linux")]
-fn get_disk() -> DiskInfo {
+fn get_disks(config: &DiskConfig) -> Vec<DiskInfo> {
     use libc::statvfs;
     use std::ffi::CString;
     use std::fs;
 
-    let mut total: u64 = 0;
-    let mut free: u64 = 0;
-
-    // Filesystem types to ignore (virtual/special filesystems)
-    let fs_types_to_ignore = [
-        "sysfs",
-        "proc",
-        "devpts",
-        "tmpfs",
-        "devtmpfs",
-        "debugfs",
-        "securityfs",
-        "cgroup",
-        "pstore",
-        "autofs",
-        "mqueue",
-        "hugetlbfs",
-        "fusectl",
-        "fuse.gvfsd-fuse",
-    ];
+    let mut disks = Vec::new();
+
+    // Bind mounts share the st_dev of their source, btrfs subvolumes share the device
+    let mut seen_devices: Vec<String> = Vec::new();
+    let mut seen_dev_ids: Vec<u64> = Vec::new();
 
     // Read mounted filesystems from /proc/mounts
     if let Ok(mounts) = fs::read_to_string("/proc/mounts") {
@@ -53,17 +104,27 @@ fn get_disk() -> DiskInfo {
                 continue; // Skip invalid lines
             }
 
-            let device = parts[0];
-            let mount_point = parts[1];
+            let device = unescape_mount(parts[0]);
+            let mount_point = unescape_mount(parts[1]);
             let fs_type = parts[2];
 
             // Skip virtual/special filesystems
-            if fs_types_to_ignore.contains(&fs_type) || device.starts_with("none") {
+            if FS_TYPES_TO_IGNORE.contains(&fs_type) || device.starts_with("none") {
+                continue;
+            }
+
+            // Container layers report the filesystem they are stacked on, so they
+            // would be counted twice. Only a container's own root is kept
+            if fs_type == "overlay" && mount_point != "/" {
+                continue;
+            }
+
+            if !is_mount_selected(config, &mount_point, fs_type) {
                 continue;
             }
 
             // Convert mount point to C string for statvfs call
-            let c_path = match CString::new(mount_point) {
+            let c_path = match CString::new(mount_point.as_str()) {
                 Ok(c) => c,
                 Err(_) => continue, // Skip if path conversion fails
             };
@@ -71,38 +132,54 @@ fn get_disk() -> DiskInfo {
             // Get filesystem statistics
             let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
             let res = unsafe { statvfs(c_path.as_ptr(), &mut stat) };
-            if res != 0 {
-                continue; // Skip if statvfs fails
+            if res != 0 || stat.f_blocks == 0 {
+                continue; // Skip if statvfs fails or there are no blocks
             }
 
-            let block_size = stat.f_frsize as u64;
+            // Skip the same device mounted at multiple points
+            let mut file_stat: libc::stat = unsafe { std::mem::zeroed() };
+            if unsafe { libc::stat(c_path.as_ptr(), &mut file_stat) } == 0 {
+                let dev_id = file_stat.st_dev;
+                if seen_dev_ids.contains(&dev_id) {
+                    continue;
+                }
+                seen_dev_ids.push(dev_id);
+            }
 
-            // Only count physical devices (blocks > 0) and avoid duplicate counting
-            // of the same device mounted at multiple points
-            if stat.f_blocks > 0 {
-                total += stat.f_blocks as u64 * block_size;
-                free += stat.f_bavail as u64 * block_size;
+            if device.starts_with('/') {
+                if seen_devices.contains(&device) {
+                    continue;
+                }
+                seen_devices.push(device.clone());
             }
-        }
-    }
 
-    // Calculate used space (handle potential overflow)
-    let used = if total > free { total - free } else { 0 };
+            let block_size = stat.f_frsize as u64;
+            let total = stat.f_blocks as u64 * block_size;
+            let free = stat.f_bavail as u64 * block_size;
 
-    DiskInfo {
-        used: used as usize,
-        free: free as usize,
-        total: total as usize,
+            // Reserved blocks are neither free nor used, like df
+            let used = (stat.f_blocks - stat.f_bfree) as u64 * block_size;
+
+            disks.push(DiskInfo {
+                mount: mount_point,
+                device,
+                fs_type: fs_type.to_string(),
+                used: used as usize,
+                free: free as usize,
+                total: total as usize,
+            });
+        }
     }
+
+    disks
 }
 
 #[cfg(target_os = "windows")]
-fn get_disk() -> DiskInfo {
+fn get_disks(config: &DiskConfig) -> Vec<DiskInfo> {
     use windows::core::PWSTR as CorePWSTR;
     use windows::Win32::Storage::FileSystem::{GetDiskFreeSpaceExW, GetLogicalDriveStringsW};
 
-    let mut total: u64 = 0;
-    let mut free: u64 = 0;
+    let mut disks = Vec::new();
 
     // Get list of available logical drives
     let mut drive_strings: Vec<u16> = vec![0; 260]; // MAX_PATH length buffer
@@ -124,7 +201,9 @@ fn get_disk() -> DiskInfo {
             // Process valid drive strings
             if end_pos > current_pos {
                 let drive_path = &drive_strings[current_pos..end_pos];
-                if !drive_path.is_empty() {
+                let drive = String::from_utf16_lossy(drive_path);
+
+                if !drive_path.is_empty() && is_mount_selected(config, &drive, "") {
                     // Create null-terminated copy for Windows APIs
                     let mut path_with_null = drive_path.to_vec();
                     path_with_null.push(0);
@@ -145,8 +224,14 @@ fn get_disk() -> DiskInfo {
                     };
 
                     if result.is_ok() {
-                        total += total_bytes;
-                        free += free_bytes_available;
+                        disks.push(DiskInfo {
+                            mount: drive.clone(),
+                            device: drive,
+                            used: total_bytes.saturating_sub(free_bytes_available) as usize,
+                            free: free_bytes_available as usize,
+                            total: total_bytes as usize,
+                            ..Default::default()
+                        });
                     }
                 }
             }
@@ -156,18 +241,44 @@ fn get_disk() -> DiskInfo {
         }
     }
 
-    // Calculate used space (handle potential overflow)
-    let used = if total >= free { total - free } else { 0 };
-
-    DiskInfo {
-        used: used as usize,
-        free: free as usize,
-        total: total as usize,
-    }
+    disks
 }
 
 // Loads disk information into the SystemInfo structure
-pub fn load_disk_info(info: &mut SystemInfo) {
-    let disk: DiskInfo = get_disk();
-    insert_size("disk", disk.free, disk.used, disk.total, info);
+pub fn load_disk_info(config: &DiskConfig, info: &mut SystemInfo) {
+    let disks = get_disks(config);
+
+    // Aggregate of every selected mount
+    let free = disks.iter().map(|disk| disk.free).sum();
+    let used = disks.iter().map(|disk| disk.used).sum();
+    let total = disks.iter().map(|disk| disk.total).sum();
+    insert_size("disk", free, used, total, info);
+
+    info.insert("disk_count".to_string(), disks.len().to_string());
+
+    let mut summary = Vec::new();
+    for (i, disk) in disks.iter().enumerate() {
+        let prefix = format!("disk_{}", i);
+        info.insert(format!("{}_mount", prefix), disk.mount.clone());
+        info.insert(format!("{}_device", prefix), disk.device.clone());
+        info.insert(format!("{}_fs", prefix), disk.fs_type.clone());
+        insert_size(&prefix, disk.free, disk.used, disk.total, info);
+
+        // e.g. "/home: 120.50/460.00 GB (26.20%)"
+        let value = |field: &str| {
+            info.get(&format!("{}_{}", prefix, field))
+                .cloned()
+                .unwrap_or_default()
+        };
+        summary.push(format!(
+            "{}: {}/{} {} ({}%)",
+            disk.mount,
+            value("used"),
+            value("total"),
+            value("metric"),
+            value("percentage")
+        ));
+    }
+
+    info.insert("disk_summary".to_string(), summary.join(", "));
 }
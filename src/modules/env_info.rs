@@ -17,7 +17,7 @@ pub fn load_env_info(info: &mut SystemInfo) {
     // Extract shell name (basename only)
     let shell_name = shell_var
         .split(['/', '\\'])
-        .last()
+        .next_back()
         .unwrap_or(&shell_var)
         .to_string();
     info.insert("env_shell".to_string(), shell_name);
@@ -4,46 +4,311 @@ use super::SystemInfo;
 
 struct GpuInfo {
     pub model: String,
+    pub vendor: String,
+    pub driver: String,
     pub vram: usize,
+    pub kind: String, // integrated, discrete or virtual
 }
 
 impl Default for GpuInfo {
     fn default() -> Self {
         GpuInfo {
             model: "Unknown".to_string(),
+            vendor: "Unknown".to_string(),
+            driver: "Unknown".to_string(),
             vram: 0,
+            kind: "Unknown".to_string(),
         }
     }
 }
 
+// Fallback vendor names when pci.ids is not installed
 #[cfg(target_os = "linux")]
-fn get_cpu() -> GpuInfo {
-    use std::process::Command;
+const PCI_VENDORS: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "10de" => "NVIDIA",
+    "1002" => "AMD",
+    "8086" => "Intel",
+    "1af4" => "Red Hat",
+    "1b36" => "Red Hat",
+    "1234" => "QEMU",
+    "15ad" => "VMware",
+    "80ee" => "VirtualBox",
+    "1414" => "Microsoft",
+    "1013" => "Cirrus Logic",
+    "102b" => "Matrox",
+    "1a03" => "ASPEED",
+    "5143" => "Qualcomm",
+    "14e4" => "Broadcom",
+};
 
-    let mut info = GpuInfo::default();
+// Fallback device names when pci.ids is not installed ("vendor:device")
+#[cfg(target_os = "linux")]
+const PCI_DEVICES: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "1af4:1050" => "Virtio GPU",
+    "1b36:0100" => "QXL paravirtual graphic card",
+    "1234:1111" => "QEMU Standard VGA",
+    "15ad:0405" => "SVGA II Adapter",
+    "80ee:beef" => "VirtualBox Graphics Adapter",
+    "1414:008e" => "Hyper-V Virtual Video",
+    "1013:00b8" => "GD 5446",
+    "102b:0522" => "MGA G200e",
+    "102b:0536" => "Integrated Matrox G200eW3 Graphics Controller",
+    "1a03:2000" => "ASPEED Graphics Family",
+};
+
+// AMD APU graphics device IDs, their memory carve-out can be set as large as a
+// discrete card's VRAM in the firmware
+#[cfg(target_os = "linux")]
+const AMD_APUS: &[&str] = &[
+    "9874", // Carrizo
+    "98e4", // Stoney
+    "15dd", // Raven, Picasso
+    "15d8", // Raven2, Picasso
+    "1636", // Renoir
+    "164c", // Lucienne
+    "1638", // Cezanne
+    "15e7", // Barcelo
+    "163f", // Van Gogh
+    "1681", // Rembrandt
+    "1506", // Mendocino
+    "164e", // Raphael
+    "13c0", // Granite Ridge
+    "15bf", // Phoenix
+    "15c8", // Phoenix2
+    "150e", // Strix Point
+    "1114", // Krackan Point
+    "1586", // Strix Halo
+];
+
+// Vendors whose GPUs are always emulated or paravirtualized
+#[cfg(target_os = "linux")]
+const VIRTUAL_VENDORS: &[&str] = &["1af4", "1b36", "1234", "15ad", "80ee", "1414"];
+
+#[cfg(target_os = "linux")]
+const PCI_IDS_PATHS: &[&str] = &[
+    "/usr/share/hwdata/pci.ids",
+    "/usr/share/misc/pci.ids",
+    "/usr/share/pci.ids",
+    "/var/lib/pciutils/pci.ids",
+];
+
+// Shorten vendor names, e.g. "Advanced Micro Devices, Inc. [AMD/ATI]" -> "AMD"
+#[cfg(target_os = "linux")]
+fn short_vendor(name: &str) -> String {
+    if name.starts_with("Advanced Micro Devices") {
+        return "AMD".to_string();
+    }
+
+    name.trim_end_matches(" Corporation")
+        .trim_end_matches(", Inc.")
+        .trim_end_matches(" Inc.")
+        .to_string()
+}
+
+// Prefer the marketing name, e.g. "GA102 [GeForce RTX 3080]" -> "GeForce RTX 3080"
+#[cfg(target_os = "linux")]
+fn short_device(name: &str) -> String {
+    if let (Some(start), Some(end)) = (name.find('['), name.rfind(']')) {
+        if start < end {
+            return name[start + 1..end].to_string();
+        }
+    }
+
+    name.to_string()
+}
+
+// Resolve vendor and device names from pci.ids
+#[cfg(target_os = "linux")]
+fn lookup_pci_ids(
+    pci_ids: &str,
+    vendor_id: &str,
+    device_id: &str,
+) -> (Option<String>, Option<String>) {
+    let mut vendor = None;
+
+    for line in pci_ids.lines() {
+        if line.starts_with('#') || line.is_empty() {
+            continue;
+        }
+
+        if vendor.is_none() {
+            // Vendor lines have no indentation: "10de  NVIDIA Corporation"
+            if let Some(name) = line.strip_prefix(vendor_id) {
+                if name.starts_with(' ') {
+                    vendor = Some(name.trim().to_string());
+                }
+            }
+            continue;
+        }
+
+        // End of the vendor block
+        if !line.starts_with('\t') {
+            break;
+        }
+
+        // Device lines have one tab: "\t2206  GA102 [GeForce RTX 3080]"
+        if let Some(name) = line
+            .strip_prefix('\t')
+            .and_then(|l| l.strip_prefix(device_id))
+        {
+            if name.starts_with(' ') {
+                return (vendor, Some(name.trim().to_string()));
+            }
+        }
+    }
+
+    (vendor, None)
+}
+
+#[cfg(target_os = "linux")]
+fn get_gpus() -> Vec<GpuInfo> {
+    use std::fs;
+    use std::path::Path;
 
-    if let Ok(output) = Command::new("lspci").args(["-v"]).output() {
-        let output_str = String::from_utf8_lossy(&output.stdout);
+    fn read_trimmed(path: &Path) -> Option<String> {
+        fs::read_to_string(path)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    let mut gpus = Vec::new();
+    let mut seen = Vec::new();
+
+    // Empty when pci.ids is not installed, the embedded names are used then
+    let pci_ids = PCI_IDS_PATHS
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok())
+        .unwrap_or_default();
+
+    let mut cards: Vec<_> = match fs::read_dir("/sys/class/drm") {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| {
+                // Only "cardN", not connectors like "card0-HDMI-A-1"
+                name.strip_prefix("card")
+                    .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+            })
+            .collect(),
+        Err(_) => return gpus,
+    };
+    cards.sort_by_key(|name| name[4..].parse::<usize>().unwrap_or(0));
+
+    for card in cards {
+        let device = Path::new("/sys/class/drm").join(&card).join("device");
+
+        // Several cards can share one device
+        let real_device = fs::canonicalize(&device).unwrap_or(device.clone());
+        if seen.contains(&real_device) {
+            continue;
+        }
+        seen.push(real_device.clone());
+
+        let mut gpu = GpuInfo::default();
+
+        if let Ok(driver) = fs::read_link(device.join("driver")) {
+            if let Some(name) = driver.file_name() {
+                gpu.driver = name.to_string_lossy().to_string();
+            }
+        }
+
+        // IDs look like "0x10de"
+        let id = |file: &str| {
+            read_trimmed(&device.join(file))
+                .map(|value| value.trim_start_matches("0x").to_lowercase())
+        };
+
+        match (id("vendor"), id("device")) {
+            (Some(vendor_id), Some(device_id)) => {
+                let (vendor, model) = lookup_pci_ids(&pci_ids, &vendor_id, &device_id);
+                let key = format!("{}:{}", vendor_id, device_id);
+
+                gpu.vendor = match vendor {
+                    Some(vendor) => short_vendor(&vendor),
+                    None => PCI_VENDORS
+                        .get(vendor_id.as_str())
+                        .map(|v| v.to_string())
+                        .unwrap_or_else(|| vendor_id.clone()),
+                };
+
+                let model = model
+                    .map(|m| short_device(&m))
+                    .or_else(|| PCI_DEVICES.get(key.as_str()).map(|m| m.to_string()))
+                    .unwrap_or_else(|| format!("Device {}", device_id));
+                gpu.model = format!("{} {}", gpu.vendor, model);
+
+                // Subsystem IDs tell the board vendor (e.g. ASUS, MSI)
+                if let (Some(sub_vendor), Some(sub_device)) =
+                    (id("subsystem_vendor"), id("subsystem_device"))
+                {
+                    if sub_vendor != vendor_id && sub_vendor != "0000" {
+                        let (board, _) = lookup_pci_ids(&pci_ids, &sub_vendor, &sub_device);
+                        if let Some(board) = board {
+                            gpu.vendor = format!("{} ({})", gpu.vendor, short_vendor(&board));
+                        }
+                    }
+                }
+
+                // Only amdgpu reports dedicated memory through sysfs
+                if let Some(vram) = read_trimmed(&device.join("mem_info_vram_total")) {
+                    gpu.vram = vram.parse().unwrap_or(0);
+                }
+
+                // Devices on a root bus (e.g. 0000:00:02.0 under pci0000:00) are part of
+                // the chipset or CPU, add-in cards always sit behind a PCIe bridge
+                let behind_bridge = real_device
+                    .parent()
+                    .and_then(|parent| parent.file_name())
+                    .map(|name| !name.to_string_lossy().starts_with("pci"))
+                    .unwrap_or(false);
+
+                // AMD APUs sit behind an internal bridge like add-in cards. Unknown ones
+                // are told apart by their default carve-out, a few hundred MiB next to
+                // the system memory they can map through GTT
+                let gtt: usize = read_trimmed(&device.join("mem_info_gtt_total"))
+                    .and_then(|gtt| gtt.parse().ok())
+                    .unwrap_or(0);
+                let is_apu = gpu.driver == "amdgpu"
+                    && (AMD_APUS.contains(&device_id.as_str())
+                        || (gpu.vram <= 512 << 20 && gtt > gpu.vram));
 
-        // Find GPU info
-        for line in output_str.lines() {
-            if line.contains("VGA") || line.contains("3D") || line.contains("Display") {
-                if let Some(gpu_info) = line.split(':').nth(2) {
-                    info.model = gpu_info.trim().to_string();
-                    break;
+                // 0x0302 is a 3D controller, used for the secondary GPU of hybrid laptops
+                let class = id("class").unwrap_or_default();
+
+                gpu.kind = if VIRTUAL_VENDORS.contains(&vendor_id.as_str()) {
+                    "virtual"
+                } else if class.starts_with("0302") {
+                    "discrete"
+                } else if !behind_bridge || is_apu {
+                    "integrated"
+                } else {
+                    "discrete"
                 }
+                .to_string();
+            }
+            _ => {
+                // Platform GPUs on SoCs (e.g. vc4, panfrost) have no PCI IDs
+                if gpu.driver == "Unknown" || gpu.driver == "simpledrm" {
+                    continue;
+                }
+
+                gpu.model = gpu.driver.clone();
+                gpu.kind = "integrated".to_string();
             }
         }
+
+        gpus.push(gpu);
     }
 
-    return info;
+    gpus
 }
 
 #[cfg(target_os = "windows")]
-fn get_cpu() -> GpuInfo {
+fn get_gpus() -> Vec<GpuInfo> {
     use windows::Win32::Graphics::Dxgi::*;
 
-    let mut info = GpuInfo::default();
+    let mut gpus = Vec::new();
 
     unsafe {
         if let Ok(factory) = CreateDXGIFactory::<IDXGIFactory>() {
@@ -59,9 +324,21 @@ fn get_cpu() -> GpuInfo {
                             .collect::<Vec<u16>>(),
                     );
 
-                    info.model = name;
-                    info.vram = desc.DedicatedVideoMemory;
-                    break;
+                    // Skip "Microsoft Basic Render Driver"
+                    if desc.VendorId != 0x1414 {
+                        let kind = if desc.DedicatedVideoMemory < 512 * 1024 * 1024 {
+                            "integrated"
+                        } else {
+                            "discrete"
+                        };
+
+                        gpus.push(GpuInfo {
+                            model: name,
+                            vram: desc.DedicatedVideoMemory,
+                            kind: kind.to_string(),
+                            ..Default::default()
+                        });
+                    }
                 }
 
                 i += 1;
@@ -69,14 +346,39 @@ fn get_cpu() -> GpuInfo {
         }
     }
 
-    info
+    gpus
 }
 
-pub fn load_gpu_info(info: &mut SystemInfo) {
-    let gpu = get_cpu();
-    info.insert("gpu_model".to_string(), gpu.model);
+fn insert_gpu(prefix: &str, gpu: &GpuInfo, info: &mut SystemInfo) {
+    info.insert(format!("{}_model", prefix), gpu.model.clone());
+    info.insert(format!("{}_vendor", prefix), gpu.vendor.clone());
+    info.insert(format!("{}_driver", prefix), gpu.driver.clone());
+    info.insert(format!("{}_type", prefix), gpu.kind.clone());
 
     let size = get_size(gpu.vram as f64);
-    info.insert("gpu_vram".to_string(), format!("{:.2}", size.metric_value));
-    info.insert("gpu_vram_metric".to_string(), size.metric);
+    info.insert(
+        format!("{}_vram", prefix),
+        format!("{:.2}", size.metric_value),
+    );
+    info.insert(format!("{}_vram_metric", prefix), size.metric);
+}
+
+pub fn load_gpu_info(info: &mut SystemInfo) {
+    let gpus = get_gpus();
+
+    // Prefer a discrete GPU as the primary one
+    let primary = gpus
+        .iter()
+        .find(|gpu| gpu.kind == "discrete")
+        .or(gpus.first());
+
+    match primary {
+        Some(gpu) => insert_gpu("gpu", gpu, info),
+        None => insert_gpu("gpu", &GpuInfo::default(), info),
+    }
+
+    info.insert("gpu_count".to_string(), gpus.len().to_string());
+    for (i, gpu) in gpus.iter().enumerate() {
+        insert_gpu(&format!("gpu_{}", i), gpu, info);
+    }
 }
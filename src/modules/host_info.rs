@@ -0,0 +1,219 @@
+use super::virt_info::get_hypervisor;
+use super::SystemInfo;
+use crate::utils::is_placeholder;
+
+struct HostInfo {
+    vendor: String,       // System vendor (e.g. LENOVO, Dell Inc.)
+    model: String,        // Product name (e.g. ThinkPad X1 Carbon Gen 11)
+    version: String,      // Product version
+    family: String,       // Product family
+    chassis_type: String, // Laptop, Desktop, Server, Tablet, VM, ...
+    chassis_name: String, // Decoded SMBIOS chassis type (e.g. Notebook, Mini Tower)
+}
+
+impl Default for HostInfo {
+    fn default() -> Self {
+        HostInfo {
+            vendor: "Unknown".to_string(),
+            model: "Unknown".to_string(),
+            version: "Unknown".to_string(),
+            family: "Unknown".to_string(),
+            chassis_type: "Unknown".to_string(),
+            chassis_name: "Unknown".to_string(),
+        }
+    }
+}
+
+// SMBIOS chassis types (type 3, offset 0x05) and their category
+#[cfg(target_os = "linux")]
+const CHASSIS_TYPES: phf::Map<u8, (&'static str, &'static str)> = phf::phf_map! {
+    1u8 => ("Other", "Other"),
+    3u8 => ("Desktop", "Desktop"),
+    4u8 => ("Low Profile Desktop", "Desktop"),
+    5u8 => ("Pizza Box", "Desktop"),
+    6u8 => ("Mini Tower", "Desktop"),
+    7u8 => ("Tower", "Desktop"),
+    8u8 => ("Portable", "Laptop"),
+    9u8 => ("Laptop", "Laptop"),
+    10u8 => ("Notebook", "Laptop"),
+    11u8 => ("Hand Held", "Tablet"),
+    12u8 => ("Docking Station", "Other"),
+    13u8 => ("All in One", "Desktop"),
+    14u8 => ("Sub Notebook", "Laptop"),
+    15u8 => ("Space-saving", "Desktop"),
+    16u8 => ("Lunch Box", "Desktop"),
+    17u8 => ("Main Server Chassis", "Server"),
+    18u8 => ("Expansion Chassis", "Other"),
+    19u8 => ("SubChassis", "Other"),
+    20u8 => ("Bus Expansion Chassis", "Other"),
+    21u8 => ("Peripheral Chassis", "Other"),
+    22u8 => ("RAID Chassis", "Server"),
+    23u8 => ("Rack Mount Chassis", "Server"),
+    24u8 => ("Sealed-case PC", "Desktop"),
+    25u8 => ("Multi-system Chassis", "Server"),
+    26u8 => ("Compact PCI", "Server"),
+    27u8 => ("Advanced TCA", "Server"),
+    28u8 => ("Blade", "Server"),
+    29u8 => ("Blade Enclosure", "Server"),
+    30u8 => ("Tablet", "Tablet"),
+    31u8 => ("Convertible", "Laptop"),
+    32u8 => ("Detachable", "Laptop"),
+    33u8 => ("IoT Gateway", "Embedded"),
+    34u8 => ("Embedded PC", "Embedded"),
+    35u8 => ("Mini PC", "Desktop"),
+    36u8 => ("Stick PC", "Desktop"),
+};
+
+// Device-tree chassis-type values
+#[cfg(target_os = "linux")]
+const DT_CHASSIS_TYPES: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "desktop" => "Desktop",
+    "laptop" => "Laptop",
+    "convertible" => "Laptop",
+    "server" => "Server",
+    "tablet" => "Tablet",
+    "handset" => "Handset",
+    "watch" => "Watch",
+    "embedded" => "Embedded",
+};
+
+// Board vendors by device-tree compatible prefix (e.g. "raspberrypi,4-model-b")
+#[cfg(target_os = "linux")]
+const DT_VENDORS: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "raspberrypi" => "Raspberry Pi",
+    "pine64" => "Pine64",
+    "radxa" => "Radxa",
+    "hardkernel" => "Hardkernel",
+    "friendlyarm" => "FriendlyElec",
+    "xunlong" => "Xunlong",
+    "beagle" => "BeagleBoard.org",
+    "ti" => "Texas Instruments",
+    "nvidia" => "NVIDIA",
+    "rockchip" => "Rockchip",
+    "allwinner" => "Allwinner",
+    "amlogic" => "Amlogic",
+    "qcom" => "Qualcomm",
+    "apple" => "Apple",
+    "google" => "Google",
+    "starfive" => "StarFive",
+    "sifive" => "SiFive",
+};
+
+#[cfg(target_os = "linux")]
+fn get_host() -> HostInfo {
+    use std::fs;
+
+    let mut host = HostInfo::default();
+
+    let read = |path: &str| {
+        fs::read_to_string(path)
+            .ok()
+            .map(|value| {
+                value
+                    .trim_matches(|c: char| c.is_whitespace() || c == '\0')
+                    .to_string()
+            })
+            .filter(|value| !is_placeholder(value))
+    };
+
+    if let Some(vendor) = read("/sys/class/dmi/id/sys_vendor") {
+        host.vendor = vendor;
+    }
+    if let Some(model) = read("/sys/class/dmi/id/product_name") {
+        host.model = model;
+    }
+    if let Some(version) = read("/sys/class/dmi/id/product_version") {
+        host.version = version;
+    }
+    if let Some(family) = read("/sys/class/dmi/id/product_family") {
+        host.family = family;
+    }
+
+    if let Some((name, category)) = read("/sys/class/dmi/id/chassis_type")
+        .and_then(|code| code.parse::<u8>().ok())
+        .and_then(|code| CHASSIS_TYPES.get(&code))
+    {
+        host.chassis_name = name.to_string();
+        host.chassis_type = category.to_string();
+    }
+
+    // ARM and RISC-V boards describe themselves in the device tree
+    if host.model == "Unknown" {
+        if let Some(model) = read("/sys/firmware/devicetree/base/model") {
+            host.model = model;
+        }
+
+        if host.vendor == "Unknown" {
+            if let Some(prefix) = read("/sys/firmware/devicetree/base/compatible")
+                .and_then(|compatible| compatible.split(',').next().map(|p| p.to_string()))
+            {
+                host.vendor = DT_VENDORS
+                    .get(prefix.as_str())
+                    .map(|vendor| vendor.to_string())
+                    .unwrap_or(prefix);
+            }
+        }
+
+        if let Some(chassis) = read("/sys/firmware/devicetree/base/chassis-type")
+            .and_then(|chassis| DT_CHASSIS_TYPES.get(chassis.as_str()))
+        {
+            host.chassis_name = chassis.to_string();
+            host.chassis_type = chassis.to_string();
+        }
+    }
+
+    // Hypervisors report a desktop or "Other" chassis
+    let is_vm = get_hypervisor().is_some();
+    if is_vm {
+        host.chassis_type = "VM".to_string();
+    }
+
+    host
+}
+
+#[cfg(target_os = "windows")]
+fn get_host() -> HostInfo {
+    use winreg::{enums::HKEY_LOCAL_MACHINE, RegKey};
+
+    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    let mut host = HostInfo::default();
+
+    if let Ok(key) = hklm.open_subkey("HARDWARE\\DESCRIPTION\\System\\BIOS") {
+        let read = |name: &str| {
+            key.get_value::<String, _>(name)
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !is_placeholder(value))
+        };
+
+        if let Some(vendor) = read("SystemManufacturer") {
+            host.vendor = vendor;
+        }
+        if let Some(model) = read("SystemProductName") {
+            host.model = model;
+        }
+        if let Some(version) = read("SystemVersion") {
+            host.version = version;
+        }
+        if let Some(family) = read("SystemFamily") {
+            host.family = family;
+        }
+    }
+
+    if get_hypervisor().is_some() {
+        host.chassis_type = "VM".to_string();
+    }
+
+    host
+}
+
+pub fn load_host_info(info: &mut SystemInfo) {
+    let host = get_host();
+
+    info.insert("host_vendor".to_string(), host.vendor);
+    info.insert("host_model".to_string(), host.model);
+    info.insert("host_version".to_string(), host.version);
+    info.insert("host_family".to_string(), host.family);
+    info.insert("chassis_type".to_string(), host.chassis_type);
+    info.insert("chassis_name".to_string(), host.chassis_name);
+}
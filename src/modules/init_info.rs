@@ -0,0 +1,167 @@
+use std::time::Duration;
+
+use super::SystemInfo;
+
+// Maximum time to wait for the init system's tools
+const COMMAND_TIMEOUT: Duration = Duration::from_millis(500);
+
+struct InitInfo {
+    name: String,           // Init system (e.g. systemd, OpenRC)
+    version: String,        // Init system version
+    default_target: String, // systemd default target (e.g. graphical.target)
+    failed_units: String,   // Number of failed systemd units
+}
+
+impl Default for InitInfo {
+    fn default() -> Self {
+        InitInfo {
+            name: "Unknown".to_string(),
+            version: "Unknown".to_string(),
+            default_target: "N/A".to_string(),
+            failed_units: "N/A".to_string(),
+        }
+    }
+}
+
+// Init systems by PID 1 process or executable name, with the command printing their version
+#[cfg(target_os = "linux")]
+const INIT_SYSTEMS: phf::Map<&'static str, (&'static str, &'static [&'static str])> = phf::phf_map! {
+    "systemd" => ("systemd", &["systemctl", "--version"]),
+    "openrc-init" => ("OpenRC", &["openrc", "--version"]),
+    "runit" => ("runit", &[]),
+    "runit-init" => ("runit", &[]),
+    "s6-svscan" => ("s6", &[]),
+    "s6-linux-init" => ("s6", &[]),
+    "dinit" => ("dinit", &["dinit", "--version"]),
+    "shepherd" => ("GNU Shepherd", &["herd", "--version"]),
+    "upstart" => ("Upstart", &["initctl", "--version"]),
+    "launchd" => ("launchd", &[]),
+    "busybox" => ("BusyBox", &["busybox"]),
+    "tini" => ("tini", &["tini", "--version"]),
+    "docker-init" => ("tini", &["docker-init", "--version"]),
+    "dumb-init" => ("dumb-init", &["dumb-init", "--version"]),
+    "catatonit" => ("catatonit", &["catatonit", "--version"]),
+    "s6-svscanboot" => ("s6", &[]),
+    "finit" => ("Finit", &["initctl", "-v"]),
+    "sinit" => ("sinit", &[]),
+};
+
+// A generic "init" is identified by the service manager it starts
+#[cfg(target_os = "linux")]
+fn get_generic_init() -> &'static str {
+    use std::path::Path;
+
+    if Path::new("/run/openrc").exists() {
+        "openrc-init"
+    } else if Path::new("/run/runit").exists() || Path::new("/etc/runit/1").exists() {
+        "runit"
+    } else if Path::new("/run/s6").exists() || Path::new("/run/s6-rc").exists() {
+        "s6-svscan"
+    } else if Path::new("/run/dinitctl").exists() {
+        "dinit"
+    } else {
+        "sysvinit"
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn get_systemd_state(init: &mut InitInfo) {
+    use crate::utils::run_with_timeout;
+    use std::fs;
+
+    // The default target is a symlink, e.g. to /usr/lib/systemd/system/graphical.target
+    if let Some(target) = [
+        "/etc/systemd/system/default.target",
+        "/usr/lib/systemd/system/default.target",
+        "/lib/systemd/system/default.target",
+    ]
+    .iter()
+    .find_map(|path| fs::read_link(path).ok())
+    .and_then(|target| {
+        target
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+    }) {
+        init.default_target = target;
+    }
+
+    // Unit states only live in the manager, NFailedUnits is its count of failed units
+    if let Some(failed) = run_with_timeout(
+        "systemctl",
+        &["show", "--property=NFailedUnits", "--value"],
+        COMMAND_TIMEOUT,
+    )
+    .and_then(|output| output.trim().parse::<u32>().ok())
+    {
+        init.failed_units = failed.to_string();
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn get_init() -> InitInfo {
+    use crate::utils::{get_process_name, parse_version, run_with_timeout};
+    use std::fs;
+    use std::path::Path;
+
+    let mut init = InitInfo::default();
+
+    // /proc/1/exe is only readable by root, comm is readable by everyone
+    let exe = fs::read_link("/proc/1/exe").ok().and_then(|exe| {
+        exe.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+    });
+    let comm = get_process_name(1);
+
+    // systemd creates /run/systemd/system when it boots the system
+    let mut name = if Path::new("/run/systemd/system").exists() {
+        "systemd".to_string()
+    } else {
+        match exe.or(comm) {
+            Some(name) => name,
+            None => return init,
+        }
+    };
+
+    if name == "init" {
+        name = get_generic_init().to_string();
+    }
+
+    match INIT_SYSTEMS.get(name.as_str()) {
+        Some((label, command)) => {
+            init.name = label.to_string();
+
+            if let Some((program, args)) = command.split_first() {
+                if let Some(version) = run_with_timeout(program, args, COMMAND_TIMEOUT)
+                    .and_then(|output| parse_version(output.lines().next().unwrap_or_default()))
+                {
+                    init.version = version;
+                }
+            }
+        }
+        None if name == "sysvinit" => init.name = "SysVinit".to_string(),
+        None => init.name = name,
+    }
+
+    if init.name == "systemd" {
+        get_systemd_state(&mut init);
+    }
+
+    init
+}
+
+#[cfg(target_os = "windows")]
+fn get_init() -> InitInfo {
+    InitInfo {
+        name: "Service Control Manager".to_string(),
+        ..Default::default()
+    }
+}
+
+pub fn load_init_info(info: &mut SystemInfo) {
+    let init = get_init();
+
+    info.insert("init_name".to_string(), init.name);
+    info.insert("init_version".to_string(), init.version);
+    info.insert("init_default_target".to_string(), init.default_target);
+    info.insert("init_failed_units".to_string(), init.failed_units);
+}
@@ -1,32 +1,219 @@
+#[cfg(target_os = "linux")]
+use super::virt_info::get_container;
 use super::SystemInfo;
 
 struct KernelInfo {
-    version: String,
+    name: String,                 // Kernel name (e.g. Linux)
+    version: String,              // Kernel release (e.g. 6.9.3-zen1-1-zen)
+    build: String,                // Build string (e.g. #1 ZEN SMP PREEMPT_DYNAMIC ...)
+    machine: String,              // Machine hardware name (e.g. x86_64)
+    flavor: String,               // Kernel flavor (e.g. zen, lts, rt, WSL2)
+    taint_flags: String,          // Taint flags (e.g. PO)
+    tainted: String,              // Taint reasons, or No
+    modules: Option<usize>,       // Number of loaded modules
+    latest: Option<String>,       // Newest kernel with installed modules
+    reboot_pending: Option<bool>, // A newer kernel is installed than the running one
 }
 
 impl Default for KernelInfo {
     fn default() -> Self {
         KernelInfo {
+            name: "Unknown".to_string(),
             version: "Unknown".to_string(),
+            build: "Unknown".to_string(),
+            machine: "Unknown".to_string(),
+            flavor: "Unknown".to_string(),
+            taint_flags: String::new(),
+            tainted: "No".to_string(),
+            modules: None,
+            latest: None,
+            reboot_pending: None,
         }
     }
 }
 
+// Taint flags by bit, as printed in oops messages
+#[cfg(target_os = "linux")]
+const TAINT_FLAGS: &[(char, &str)] = &[
+    ('P', "proprietary module"),
+    ('F', "forced module load"),
+    ('S', "out of spec system"),
+    ('R', "forced module unload"),
+    ('M', "machine check"),
+    ('B', "bad page"),
+    ('U', "user request"),
+    ('D', "kernel died recently"),
+    ('A', "ACPI table overridden"),
+    ('W', "kernel warning"),
+    ('C', "staging driver"),
+    ('I', "firmware workaround"),
+    ('O', "out-of-tree module"),
+    ('E', "unsigned module"),
+    ('L', "soft lockup"),
+    ('K', "live patched"),
+    ('X', "auxiliary taint"),
+    ('T', "struct randomization"),
+    ('N', "in-kernel test"),
+];
+
+// Kernel flavors by a marker in the release or build string, checked in order
+#[cfg(target_os = "linux")]
+const FLAVORS: &[(&str, &str)] = &[
+    ("microsoft-standard-WSL2", "WSL2"),
+    ("-Microsoft", "WSL"),
+    ("PREEMPT_RT", "rt"),
+    ("-rt", "rt"),
+    ("-zen", "zen"),
+    ("-lts", "lts"),
+    ("-hardened", "hardened"),
+    ("-xanmod", "xanmod"),
+    ("-lqx", "liquorix"),
+    ("-liquorix", "liquorix"),
+    ("-cachyos", "cachyos"),
+    ("-lowlatency", "lowlatency"),
+    ("-generic", "generic"),
+    ("-aws", "aws"),
+    ("-azure", "azure"),
+    ("-gcp", "gcp"),
+    ("-cloud", "cloud"),
+    ("-rpi", "rpi"),
+    ("-arch", "arch"),
+    (".fc", "fedora"),
+    (".el", "rhel"),
+    ("-amd64", "debian"),
+    ("-arm64", "debian"),
+    ("-default", "default"),
+];
+
+// Numeric parts of a release for ordering, "6.10.2-arch1-1" -> [6, 10, 2, 1, 1]
+#[cfg(target_os = "linux")]
+fn release_key(release: &str) -> Vec<u64> {
+    release
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|part| part.parse().ok())
+        .collect()
+}
+
+// Words of a release that tell its flavor apart, "6.8.0-45-generic" -> ["generic"],
+// so generic and lowlatency (or lts and zen) kernels are not compared with each other
+#[cfg(target_os = "linux")]
+fn flavor_key(release: &str) -> Vec<&str> {
+    release
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+// Kernels with modules installed, newest first
+#[cfg(target_os = "linux")]
+fn get_installed_kernels() -> Option<Vec<String>> {
+    use std::fs;
+    use std::path::Path;
+
+    let dir = ["/usr/lib/modules", "/lib/modules"]
+        .iter()
+        .find(|dir| Path::new(dir).is_dir())?;
+
+    // Removed kernels can leave directories with only DKMS leftovers behind
+    let mut kernels: Vec<String> = fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| {
+            let path = entry.path();
+            path.join("modules.dep").exists() || path.join("vmlinuz").exists()
+        })
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+
+    kernels.sort_by_key(|release| std::cmp::Reverse(release_key(release)));
+    Some(kernels)
+}
+
 #[cfg(target_os = "linux")]
 fn get_kernel() -> KernelInfo {
-    use std::process::Command;
+    use std::ffi::CStr;
+    use std::fs;
 
     let mut info = KernelInfo::default();
-    if let Ok(output) = Command::new("uname").arg("-r").output() {
-        info.version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    let mut uts: libc::utsname = unsafe { std::mem::zeroed() };
+    if unsafe { libc::uname(&mut uts) } == 0 {
+        let field = |value: &[libc::c_char]| unsafe {
+            CStr::from_ptr(value.as_ptr()).to_string_lossy().to_string()
+        };
+        info.name = field(&uts.sysname);
+        info.version = field(&uts.release);
+        info.build = field(&uts.version);
+        info.machine = field(&uts.machine);
+    } else if let Ok(release) = fs::read_to_string("/proc/sys/kernel/osrelease") {
+        info.version = release.trim().to_string();
     }
-    return info;
+
+    let tagged = format!("{} {}", info.version, info.build);
+    info.flavor = FLAVORS
+        .iter()
+        .find(|(marker, _)| tagged.contains(marker))
+        .map(|(_, flavor)| flavor.to_string())
+        .unwrap_or_else(|| "vanilla".to_string());
+
+    let taint: u64 = fs::read_to_string("/proc/sys/kernel/tainted")
+        .ok()
+        .and_then(|taint| taint.trim().parse().ok())
+        .unwrap_or(0);
+    let set: Vec<&(char, &str)> = TAINT_FLAGS
+        .iter()
+        .enumerate()
+        .filter(|(bit, _)| taint & (1 << bit) != 0)
+        .map(|(_, flag)| flag)
+        .collect();
+
+    // e.g. "PO" and "proprietary module, out-of-tree module"
+    if !set.is_empty() {
+        info.taint_flags = set.iter().map(|(letter, _)| letter).collect();
+        info.tainted = set
+            .iter()
+            .map(|(_, reason)| *reason)
+            .collect::<Vec<&str>>()
+            .join(", ");
+    }
+
+    info.modules = fs::read_to_string("/proc/modules")
+        .ok()
+        .map(|modules| modules.lines().count());
+
+    // Containers and chroots run the host's kernel, the kernels installed there
+    // (if any) are not the ones it booted from
+    if let Some(kernels) = get_installed_kernels().filter(|_| get_container().is_none()) {
+        let running = release_key(&info.version);
+        let flavor = flavor_key(&info.version);
+        let same_flavor: Vec<&String> = kernels
+            .iter()
+            .filter(|release| flavor_key(release) == flavor)
+            .collect();
+
+        // The running kernel's modules are gone when it was upgraded in place
+        if !same_flavor.is_empty() {
+            info.reboot_pending = Some(
+                !kernels.contains(&info.version)
+                    || same_flavor
+                        .iter()
+                        .any(|release| release_key(release) > running),
+            );
+            info.latest = same_flavor.first().map(|release| release.to_string());
+        }
+    }
+
+    info
 }
 
 #[cfg(target_os = "windows")]
 fn get_kernel() -> KernelInfo {
     use windows::Win32::System::SystemInformation::OSVERSIONINFOW;
-    let mut info = KernelInfo::default();
+    let mut info = KernelInfo {
+        name: "Windows NT".to_string(),
+        flavor: "N/A".to_string(),
+        ..Default::default()
+    };
 
     extern "system" {
         fn RtlGetVersion(lpVersionInformation: *mut OSVERSIONINFOW) -> i32;
@@ -45,13 +232,54 @@ fn get_kernel() -> KernelInfo {
                 version_info.dwMinorVersion,
                 version_info.dwBuildNumber
             );
+            info.build = version_info.dwBuildNumber.to_string();
         }
     }
 
+    if let Ok(machine) = std::env::var("PROCESSOR_ARCHITECTURE") {
+        info.machine = machine;
+    }
+
     info
 }
 
 pub fn load_kernel_info(info: &mut SystemInfo) {
     let kernel = get_kernel();
-    info.insert("kernel_version".to_string(), kernel.version);
+
+    let unknown = || "Unknown".to_string();
+
+    info.insert("kernel_name".to_string(), kernel.name);
+    info.insert("kernel_version".to_string(), kernel.version.clone());
+    info.insert("kernel_release".to_string(), kernel.version);
+    info.insert("kernel_build".to_string(), kernel.build);
+    info.insert("kernel_machine".to_string(), kernel.machine);
+    info.insert("kernel_flavor".to_string(), kernel.flavor);
+    info.insert("kernel_taint_flags".to_string(), kernel.taint_flags);
+    info.insert("kernel_tainted".to_string(), kernel.tainted);
+    info.insert(
+        "kernel_modules".to_string(),
+        kernel
+            .modules
+            .map(|count| count.to_string())
+            .unwrap_or_else(unknown),
+    );
+    info.insert(
+        "kernel_latest".to_string(),
+        kernel.latest.unwrap_or_else(unknown),
+    );
+    info.insert(
+        "kernel_reboot_pending".to_string(),
+        match kernel.reboot_pending {
+            Some(true) => "Yes".to_string(),
+            Some(false) => "No".to_string(),
+            None => unknown(),
+        },
+    );
+    info.insert(
+        "kernel_reboot_warning".to_string(),
+        match kernel.reboot_pending {
+            Some(true) => "(reboot pending)".to_string(),
+            _ => String::new(),
+        },
+    );
 }
@@ -1,45 +1,194 @@
-use crate::utils::insert_size;
+use crate::cgroup::{get_cgroup_limits, CgroupLimits};
+use crate::config::MemoryConfig;
+use crate::utils::{get_size, insert_size};
 
 use super::SystemInfo;
 
+#[derive(Default)]
 struct MemoryInfo {
     total: usize,
     free: usize,
     available: Option<usize>,
+    buffers: usize,
+    cached: usize, // Page cache plus reclaimable slab
+    shared: usize,
+    swap_total: usize,
+    swap_free: usize,
+    swap_devices: Vec<SwapDevice>,
+    zram_devices: Vec<ZramDevice>,
+    zswap: Option<ZswapInfo>,
+    huge_pages: Option<HugePagesInfo>,
 }
 
+struct SwapDevice {
+    name: String,     // Device or file path
+    kind: String,     // partition or file
+    priority: String, // Swap priority
+    used: usize,
+    total: usize,
+}
+
+struct ZramDevice {
+    name: String,       // e.g. zram0
+    algorithm: String,  // Compression algorithm (e.g. zstd)
+    original: usize,    // Data stored, before compression
+    compressed: usize,  // Data stored, after compression
+    memory_used: usize, // Memory used including allocator overhead
+}
+
+struct ZswapInfo {
+    enabled: bool,
+    compressor: String,
+    pool: usize,   // Memory used by the compressed pool
+    stored: usize, // Data stored in the pool, before compression
+}
+
+struct HugePagesInfo {
+    total: usize,        // Number of reserved huge pages
+    free: usize,         // Number of unused huge pages
+    size: usize,         // Huge page size in bytes
+    transparent: String, // Transparent huge pages mode (always, madvise or never)
+}
+
+// The selected value in sysfs choice files, e.g. "always [madvise] never" -> "madvise"
 #[cfg(target_os = "linux")]
-fn get_memory() -> MemoryInfo {
+fn get_selected(choices: &str) -> Option<String> {
+    let start = choices.find('[')?;
+    let end = choices[start..].find(']')?;
+    Some(choices[start + 1..start + end].to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn get_swap_devices() -> Vec<SwapDevice> {
     use std::fs;
 
-    let mut total_kb: usize = 0;
-    let mut free_kb: usize = 0;
-    let mut available_kb: Option<usize> = None;
-
-    if let Ok(mem_info) = fs::read_to_string("/proc/meminfo") {
-        for line in mem_info.lines() {
-            if line.starts_with("MemTotal:") {
-                total_kb = line
-                    .split_whitespace()
-                    .nth(1)
-                    .and_then(|s| s.parse().ok())
-                    .unwrap_or(0);
-            } else if line.starts_with("MemFree:") {
-                free_kb = line
-                    .split_whitespace()
-                    .nth(1)
-                    .and_then(|s| s.parse().ok())
-                    .unwrap_or(0);
-            } else if line.starts_with("MemAvailable:") {
-                available_kb = line.split_whitespace().nth(1).and_then(|s| s.parse().ok());
+    // Filename  Type  Size  Used  Priority, sizes in KiB
+    let swaps = fs::read_to_string("/proc/swaps").unwrap_or_default();
+
+    swaps
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 5 {
+                return None;
             }
-        }
+
+            Some(SwapDevice {
+                name: parts[0].replace("\\040", " "),
+                kind: parts[1].to_string(),
+                total: parts[2].parse::<usize>().ok()? * 1024,
+                used: parts[3].parse::<usize>().ok()? * 1024,
+                priority: parts[4].to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn get_zram_devices() -> Vec<ZramDevice> {
+    use std::fs;
+
+    let mut devices = Vec::new();
+
+    let mut names: Vec<String> = match fs::read_dir("/sys/block") {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| name.starts_with("zram"))
+            .collect(),
+        Err(_) => return devices,
     };
+    names.sort();
+
+    for name in names {
+        let path = format!("/sys/block/{}", name);
+
+        // Unconfigured devices have no disk size
+        let disksize = fs::read_to_string(format!("{}/disksize", path))
+            .ok()
+            .and_then(|size| size.trim().parse::<usize>().ok())
+            .unwrap_or(0);
+        if disksize == 0 {
+            continue;
+        }
+
+        // orig_data_size compr_data_size mem_used_total ...
+        let stats: Vec<usize> = fs::read_to_string(format!("{}/mm_stat", path))
+            .unwrap_or_default()
+            .split_whitespace()
+            .filter_map(|value| value.parse().ok())
+            .collect();
+
+        let algorithm = fs::read_to_string(format!("{}/comp_algorithm", path))
+            .ok()
+            .and_then(|choices| get_selected(&choices))
+            .unwrap_or_else(|| "Unknown".to_string());
+
+        devices.push(ZramDevice {
+            name,
+            algorithm,
+            original: stats.first().copied().unwrap_or(0),
+            compressed: stats.get(1).copied().unwrap_or(0),
+            memory_used: stats.get(2).copied().unwrap_or(0),
+        });
+    }
+
+    devices
+}
+
+#[cfg(target_os = "linux")]
+fn get_memory() -> MemoryInfo {
+    use std::collections::HashMap;
+    use std::fs;
+
+    // "MemTotal:       16318412 kB", huge page counts have no unit
+    let mem_info: HashMap<String, usize> = fs::read_to_string("/proc/meminfo")
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let value = value.split_whitespace().next()?.parse().ok()?;
+            Some((key.trim().to_string(), value))
+        })
+        .collect();
+
+    let bytes = |key: &str| mem_info.get(key).copied().unwrap_or(0) * 1024;
+
+    let zswap = fs::read_to_string("/sys/module/zswap/parameters/enabled")
+        .ok()
+        .map(|enabled| ZswapInfo {
+            enabled: enabled.trim() == "Y",
+            compressor: fs::read_to_string("/sys/module/zswap/parameters/compressor")
+                .map(|compressor| compressor.trim().to_string())
+                .unwrap_or_else(|_| "Unknown".to_string()),
+            pool: bytes("Zswap"),
+            stored: bytes("Zswapped"),
+        });
+
+    let huge_pages = mem_info.get("HugePages_Total").map(|&total| HugePagesInfo {
+        total,
+        free: mem_info.get("HugePages_Free").copied().unwrap_or(0),
+        size: bytes("Hugepagesize"),
+        transparent: fs::read_to_string("/sys/kernel/mm/transparent_hugepage/enabled")
+            .ok()
+            .and_then(|choices| get_selected(&choices))
+            .unwrap_or_else(|| "Unknown".to_string()),
+    });
 
     MemoryInfo {
-        total: total_kb * 1024,
-        free: free_kb * 1024,
-        available: available_kb,
+        total: bytes("MemTotal"),
+        free: bytes("MemFree"),
+        available: mem_info.get("MemAvailable").map(|kb| kb * 1024),
+        buffers: bytes("Buffers"),
+        cached: bytes("Cached") + bytes("SReclaimable"),
+        shared: bytes("Shmem"),
+        swap_total: bytes("SwapTotal"),
+        swap_free: bytes("SwapFree"),
+        swap_devices: get_swap_devices(),
+        zram_devices: get_zram_devices(),
+        zswap,
+        huge_pages,
     }
 }
 
@@ -54,33 +203,213 @@ fn get_memory() -> MemoryInfo {
         };
 
         if GlobalMemoryStatusEx(&mut status).is_ok() {
+            // The page file limit includes physical memory
             MemoryInfo {
                 total: (status.ullTotalPhys) as usize,
                 free: (status.ullAvailPhys) as usize,
                 available: Some((status.ullAvailPhys) as usize),
+                swap_total: status.ullTotalPageFile.saturating_sub(status.ullTotalPhys) as usize,
+                swap_free: status.ullAvailPageFile.saturating_sub(status.ullAvailPhys) as usize,
+                ..Default::default()
             }
         } else {
-            MemoryInfo {
-                total: 0,
-                free: 0,
-                available: None,
-            }
+            MemoryInfo::default()
         }
     }
 }
 
-pub fn load_memory_info(info: &mut SystemInfo) {
+// Used memory, following the definition selected in the config
+fn get_used(config: &MemoryConfig, memory: &MemoryInfo) -> usize {
+    let available = memory.available.unwrap_or(memory.free);
+
+    match config.used.as_str() {
+        // Everything not free, page cache included
+        "free" => memory.total.saturating_sub(memory.free),
+        // free(1) before procps-ng 3.3.10, neofetch
+        "buffers_cache" => memory
+            .total
+            .saturating_sub(memory.free + memory.buffers + memory.cached),
+        // free(1), htop, btop: what can't be given to new programs without swapping
+        _ => memory.total.saturating_sub(available),
+    }
+}
+
+// Memory of the cgroup seen as a machine of its own, with the limit as its total
+fn get_cgroup_memory(limits: &CgroupLimits, memory: &MemoryInfo) -> Option<MemoryInfo> {
+    let total = memory.total.min(limits.memory_limit?);
+    let current = limits.memory_current?;
+    let stat = |key: &str| limits.memory_stat.get(key).copied().unwrap_or(0);
+
+    let free = total.saturating_sub(current);
+    Some(MemoryInfo {
+        total,
+        free,
+        // Inactive page cache is reclaimed before the limit is hit
+        available: Some((free + stat("inactive_file")).min(total)),
+        // Buffers are not accounted separately, they are part of "file"
+        buffers: 0,
+        cached: stat("file"),
+        shared: stat("shmem"),
+        ..Default::default()
+    })
+}
+
+pub fn load_memory_info(config: &MemoryConfig, info: &mut SystemInfo) {
     let memory = get_memory();
-    let used = memory.total - memory.free;
-    insert_size("memory", memory.free, used, memory.total, info);
+    let limits = get_cgroup_limits();
+
+    // Inside a container the cgroup limit is what programs can actually use
+    let cgroup = if config.cgroup_limits {
+        get_cgroup_memory(&limits, &memory)
+    } else {
+        None
+    };
+    let view = cgroup.as_ref().unwrap_or(&memory);
+
+    let used = get_used(config, view);
+    insert_size(
+        "memory",
+        view.total.saturating_sub(used),
+        used,
+        view.total,
+        info,
+    );
+
+    let limit = match limits.memory_limit {
+        Some(limit) => {
+            let limit = get_size(limit as f64);
+            format!("{:.2} {}", limit.metric_value, limit.metric)
+        }
+        None => "None".to_string(),
+    };
+    info.insert("memory_limit".to_string(), limit);
 
-    if memory.available.is_some() {
-        let available = memory.available.unwrap();
-        let used = memory.total - available;
-        insert_size("memory_available", available, used, memory.total, info);
+    if let Some(available) = view.available {
+        let used = view.total.saturating_sub(available);
+        insert_size("memory_available", available, used, view.total, info);
     } else {
         info.insert("memory_available_free".to_string(), "N/A".to_string());
         info.insert("memory_available_used".to_string(), "N/A".to_string());
         info.insert("memory_available_total".to_string(), "N/A".to_string());
     }
+
+    // Expressed in memory_metric, like memory_used
+    let total = get_size(view.total as f64);
+    for (name, value) in [
+        ("buffers", view.buffers),
+        ("cached", view.cached),
+        ("shared", view.shared),
+    ] {
+        if cgroup.is_some() && name == "buffers" {
+            info.insert("memory_buffers".to_string(), "N/A".to_string());
+            continue;
+        }
+
+        let value = get_size(value as f64).value_in(&total.metric);
+        info.insert(format!("memory_{}", name), format!("{:.2}", value));
+    }
+
+    let swap_used = memory.swap_total.saturating_sub(memory.swap_free);
+    insert_size("swap", memory.swap_free, swap_used, memory.swap_total, info);
+
+    info.insert(
+        "swap_count".to_string(),
+        memory.swap_devices.len().to_string(),
+    );
+
+    let mut summary = Vec::new();
+    for (i, device) in memory.swap_devices.iter().enumerate() {
+        let prefix = format!("swap_{}", i);
+        info.insert(format!("{}_name", prefix), device.name.clone());
+        info.insert(format!("{}_type", prefix), device.kind.clone());
+        info.insert(format!("{}_priority", prefix), device.priority.clone());
+        insert_size(
+            &prefix,
+            device.total.saturating_sub(device.used),
+            device.used,
+            device.total,
+            info,
+        );
+
+        // e.g. "/dev/zram0: 0.50/8.00 GB"
+        let value = |field: &str| {
+            info.get(&format!("{}_{}", prefix, field))
+                .cloned()
+                .unwrap_or_default()
+        };
+        summary.push(format!(
+            "{}: {}/{} {}",
+            device.name,
+            value("used"),
+            value("total"),
+            value("metric")
+        ));
+    }
+    info.insert("swap_summary".to_string(), summary.join(", "));
+
+    // Compression ratio is the data stored over the memory it takes
+    let ratio = |original: usize, compressed: usize| {
+        if compressed > 0 {
+            format!("{:.2}", original as f64 / compressed as f64)
+        } else {
+            "N/A".to_string()
+        }
+    };
+
+    info.insert(
+        "zram_count".to_string(),
+        memory.zram_devices.len().to_string(),
+    );
+
+    for (i, device) in memory.zram_devices.iter().enumerate() {
+        let prefix = format!("zram_{}", i);
+        let used = get_size(device.memory_used as f64);
+        info.insert(format!("{}_name", prefix), device.name.clone());
+        info.insert(format!("{}_algorithm", prefix), device.algorithm.clone());
+        info.insert(
+            format!("{}_ratio", prefix),
+            ratio(device.original, device.compressed),
+        );
+        info.insert(
+            format!("{}_used", prefix),
+            format!("{:.2} {}", used.metric_value, used.metric),
+        );
+    }
+
+    let original = memory.zram_devices.iter().map(|d| d.original).sum();
+    let compressed = memory.zram_devices.iter().map(|d| d.compressed).sum();
+    info.insert("zram_ratio".to_string(), ratio(original, compressed));
+
+    match &memory.zswap {
+        Some(zswap) => {
+            let pool = get_size(zswap.pool as f64);
+            info.insert(
+                "zswap_enabled".to_string(),
+                if zswap.enabled { "Yes" } else { "No" }.to_string(),
+            );
+            info.insert("zswap_compressor".to_string(), zswap.compressor.clone());
+            info.insert(
+                "zswap_pool".to_string(),
+                format!("{:.2} {}", pool.metric_value, pool.metric),
+            );
+            info.insert("zswap_ratio".to_string(), ratio(zswap.stored, zswap.pool));
+        }
+        None => {
+            info.insert("zswap_enabled".to_string(), "N/A".to_string());
+        }
+    }
+
+    if let Some(huge_pages) = &memory.huge_pages {
+        let size = get_size(huge_pages.size as f64);
+        info.insert("hugepages_total".to_string(), huge_pages.total.to_string());
+        info.insert("hugepages_free".to_string(), huge_pages.free.to_string());
+        info.insert(
+            "hugepages_size".to_string(),
+            format!("{:.0} {}", size.metric_value, size.metric),
+        );
+        info.insert(
+            "hugepages_transparent".to_string(),
+            huge_pages.transparent.clone(),
+        );
+    }
 }
@@ -2,30 +2,50 @@ use crate::{config::Config, constants::is_debug};
 use std::{collections::HashMap, process::Command, time::Instant};
 
 use bios_info::load_bios_info;
+use boot_info::load_boot_info;
 use cpu_info::load_cpu_info;
+use desktop_info::load_desktop_info;
 use disk_info::load_disk_info;
 use env_info::load_env_info;
 use gpu_info::load_gpu_info;
+use host_info::load_host_info;
+use init_info::load_init_info;
 use kernel_info::load_kernel_info;
 use memory_info::load_memory_info;
 use net_info::load_net_info;
 use os_info::load_os_info;
 use packages_info::load_packages_info;
+use ram_info::load_ram_info;
 use screen_info::load_screen_info;
+use shell_info::load_shell_info;
+use storage_info::load_storage_info;
+use terminal_info::load_terminal_info;
+use theme_info::load_theme_info;
 use uptime_info::load_uptime_info;
+use virt_info::load_virt_info;
 
 pub mod bios_info;
+pub mod boot_info;
 pub mod cpu_info;
+pub mod desktop_info;
 pub mod disk_info;
 pub mod env_info;
 pub mod gpu_info;
+pub mod host_info;
+pub mod init_info;
 pub mod kernel_info;
 pub mod memory_info;
 pub mod net_info;
 pub mod os_info;
 pub mod packages_info;
+pub mod ram_info;
 pub mod screen_info;
+pub mod shell_info;
+pub mod storage_info;
+pub mod terminal_info;
+pub mod theme_info;
 pub mod uptime_info;
+pub mod virt_info;
 
 pub struct SystemInfo {
     info: HashMap<String, String>,
@@ -57,18 +77,28 @@ pub fn get_system_info(config: &Config) -> SystemInfo {
         let start_time = if debug { Some(Instant::now()) } else { None };
 
         match module.as_str() {
-            "os" => load_os_info(&mut system_info),
+            "os" => load_os_info(&config.modules.os, &mut system_info),
             "kernel" => load_kernel_info(&mut system_info),
             "uptime" => load_uptime_info(&mut system_info),
-            "packages" => load_packages_info(&mut system_info),
+            "packages" => load_packages_info(&config.modules.packages, &mut system_info),
             "env" => load_env_info(&mut system_info),
-            "cpu" => load_cpu_info(&mut system_info),
+            "cpu" => load_cpu_info(&config.modules.cpu, &mut system_info),
             "gpu" => load_gpu_info(&mut system_info),
-            "memory" => load_memory_info(&mut system_info),
+            "memory" => load_memory_info(&config.modules.memory, &mut system_info),
             "screen" => load_screen_info(&mut system_info),
             "bios" => load_bios_info(&mut system_info),
-            "disk" => load_disk_info(&mut system_info),
-            "net" => load_net_info(&mut system_info),
+            "disk" => load_disk_info(&config.modules.disk, &mut system_info),
+            "net" => load_net_info(&config.modules.net, &mut system_info),
+            "desktop" => load_desktop_info(&mut system_info),
+            "theme" => load_theme_info(&mut system_info),
+            "terminal" => load_terminal_info(&mut system_info),
+            "shell" => load_shell_info(&mut system_info),
+            "storage" => load_storage_info(&config.modules.storage, &mut system_info),
+            "ram" => load_ram_info(&mut system_info),
+            "host" => load_host_info(&mut system_info),
+            "virt" => load_virt_info(&mut system_info),
+            "init" => load_init_info(&mut system_info),
+            "boot" => load_boot_info(&mut system_info),
             _ => {}
         }
 
@@ -1,331 +1,1831 @@
+use std::time::Duration;
+
 use super::SystemInfo;
+use crate::config::NetConfig;
+use crate::utils::get_size;
+
+#[derive(Clone, Copy)]
+struct Counters {
+    rx_bytes: u64,
+    tx_bytes: u64,
+    rx_packets: u64,
+    tx_packets: u64,
+}
+
+#[derive(Default)]
+struct Interface {
+    name: String,
+    index: u32,
+    kind: &'static str, // ethernet, wireless, bridge, veth, tun, wireguard, ... (see INTERFACE_KINDS)
+    mac: Option<String>, // None for tun, wireguard and other layer 3 interfaces
+    physical_lower: bool, // Bridge, bond or VLAN on top of a physical interface
+    up: bool,
+    loopback: bool,
+    ipv4: Vec<String>, // Addresses with prefix length, e.g. 192.168.1.10/24
+    ipv6: Vec<String>, // e.g. 2001:db8::10/64, fe80::1/64
+    counters: Option<Counters>,
+    throughput: Option<(f64, f64)>, // Received and transmitted bytes per second
+    speed: Option<u64>,             // Link speed in Mbit/s
+    duplex: Option<String>,         // full or half
+    mtu: Option<u32>,
+    operstate: Option<String>, // RFC 2863 state, e.g. up, down, dormant
+    driver: Option<String>,    // Kernel driver, the adapter description on Windows
+}
+
+// Interface kinds, and whether they are virtual or a VPN tunnel. A tap is usually a VM's
+// NIC and ppp a PPPoE or modem uplink, only PPP over L2TP is a tunnel
+const INTERFACE_KINDS: phf::Map<&'static str, (bool, bool)> = phf::phf_map! {
+    "ethernet" => (false, false),
+    "wireless" => (false, false),
+    "loopback" => (true, false),
+    "bridge" => (true, false),
+    "veth" => (true, false),
+    "vlan" => (true, false),
+    "bond" => (true, false),
+    "tun" => (true, true),
+    "tap" => (true, false),
+    "wireguard" => (true, true),
+    "ppp" => (true, false),
+    "l2tp" => (true, true),
+    "virtual" => (true, false),
+};
+
+fn is_virtual(kind: &str) -> bool {
+    INTERFACE_KINDS
+        .get(kind)
+        .is_none_or(|(is_virtual, _)| *is_virtual)
+}
+
+fn is_vpn(kind: &str) -> bool {
+    INTERFACE_KINDS.get(kind).is_some_and(|(_, is_vpn)| *is_vpn)
+}
+
+#[derive(Default)]
+struct WifiInfo {
+    iface: String,
+    ssid: Option<String>, // None when not connected
+    bssid: Option<String>,
+    signal: Option<i32>,      // dBm
+    quality: Option<u32>,     // Percentage
+    frequency: Option<u32>,   // MHz
+    channel: Option<u32>,     // IEEE 802.11 channel number
+    width: Option<u32>,       // Channel width in MHz
+    bitrate: Option<u32>,     // Transmit bitrate in kbit/s
+    security: Option<String>, // e.g. WPA2-Personal, WPA3-Personal, Open
+}
 
 struct NetInfo {
-    pub ip: String,
-    pub mac: String,
-    pub iface: String,
-    pub wifi_ssid: String,
-    pub wifi_signal: String,
+    pub iface: String,              // Interface holding the default route
+    pub gateway: String,            // IPv4 default gateway
+    pub gateway6: String,           // IPv6 default gateway
+    pub dns: Vec<String>,           // DNS servers
+    pub interfaces: Vec<Interface>, // Every interface, in system order
+    pub wifi: Option<WifiInfo>,     // First connected wireless interface, the primary one first
 }
 
 impl Default for NetInfo {
     fn default() -> Self {
         NetInfo {
-            ip: "unknown".to_string(),
-            mac: "unknown".to_string(),
             iface: "unknown".to_string(),
-            wifi_ssid: "unknown".to_string(),
-            wifi_signal: "unknown".to_string(),
+            gateway: "None".to_string(),
+            gateway6: "None".to_string(),
+            dns: Vec::new(),
+            interfaces: Vec::new(),
+            wifi: None,
         }
     }
 }
 
+// Default IPv4 routes from /proc/net/route by metric, as (interface, gateway)
 #[cfg(target_os = "linux")]
-fn get_net() -> NetInfo {
-    use pnet::datalink;
-    use std::process::Command;
-    use std::str;
+fn get_default_routes() -> Vec<(String, String)> {
+    use std::net::Ipv4Addr;
 
-    // println!("[DEBUG] Starting network information collection on Linux");
+    let content = std::fs::read_to_string("/proc/net/route").unwrap_or_default();
 
-    // Start with default values
-    let mut net_info = NetInfo::default();
+    // Iface Destination Gateway Flags RefCnt Use Metric Mask ...
+    // eth0  00000000    0101A8C0 0003  0      0   100    00000000
+    let mut routes: Vec<(u32, &str, Ipv4Addr)> = content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let flags = u32::from_str_radix(fields.get(3)?, 16).ok()?;
+            if fields[1] != "00000000" || fields.get(7)? != &"00000000" || flags & 0x1 == 0 {
+                return None;
+            }
 
-    // Get active network interfaces
-    let interfaces = match datalink::interfaces() {
-        interfaces if !interfaces.is_empty() => {
-            // println!("[DEBUG] Found {} network interfaces", interfaces.len());
-            interfaces
-        }
-        _ => {
-            // println!("[DEBUG] No network interfaces found");
-            return net_info;
+            // Addresses are printed as the raw network order u32
+            let gateway = u32::from_str_radix(fields[2], 16).ok()?;
+            let metric: u32 = fields.get(6)?.parse().ok()?;
+            Some((metric, fields[0], Ipv4Addr::from(gateway.to_ne_bytes())))
+        })
+        .collect();
+
+    routes.sort_by_key(|(metric, _, _)| *metric);
+    routes
+        .into_iter()
+        .map(|(_, iface, gateway)| {
+            // Point-to-point links such as VPN tunnels route without a gateway
+            let gateway = match gateway.is_unspecified() {
+                true => "None".to_string(),
+                false => gateway.to_string(),
+            };
+            (iface.to_string(), gateway)
+        })
+        .collect()
+}
+
+// Default IPv6 routes from /proc/net/ipv6_route by metric, as (interface, gateway)
+#[cfg(target_os = "linux")]
+fn get_default_routes6() -> Vec<(String, String)> {
+    use std::net::Ipv6Addr;
+
+    let content = std::fs::read_to_string("/proc/net/ipv6_route").unwrap_or_default();
+
+    // Destination PrefixLen Source PrefixLen NextHop Metric RefCnt Use Flags Iface
+    let mut routes: Vec<(u32, &str, Ipv6Addr)> = content
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let flags = u32::from_str_radix(fields.get(8)?, 16).ok()?;
+
+            // Up and not a reject route, which the kernel keeps on lo
+            if !fields[0].trim_matches('0').is_empty() || fields[1] != "00" || flags & 0x1 == 0 {
+                return None;
+            }
+            if flags & 0x200 != 0 || fields.get(9)? == &"lo" {
+                return None;
+            }
+
+            let gateway = Ipv6Addr::from(u128::from_str_radix(fields[4], 16).ok()?);
+            let metric = u32::from_str_radix(fields[5], 16).ok()?;
+            Some((metric, fields[9], gateway))
+        })
+        .collect();
+
+    routes.sort_by_key(|(metric, _, _)| *metric);
+    routes
+        .into_iter()
+        .map(|(_, iface, gateway)| {
+            let gateway = match gateway.is_unspecified() {
+                true => "None".to_string(),
+                false => gateway.to_string(),
+            };
+            (iface.to_string(), gateway)
+        })
+        .collect()
+}
+
+// Nameservers from resolv.conf; with systemd-resolved it only lists the local stub
+// resolver, so the upstream servers come from the file resolved writes for them
+#[cfg(target_os = "linux")]
+fn get_dns() -> Vec<String> {
+    let nameservers = |path: &str| -> Vec<String> {
+        std::fs::read_to_string(path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| line.strip_prefix("nameserver"))
+            .map(|server| server.trim().to_string())
+            .filter(|server| !server.is_empty())
+            .collect()
+    };
+
+    let servers = nameservers("/etc/resolv.conf");
+    if servers.iter().all(|server| server.starts_with("127.0.0.5")) {
+        let upstream = nameservers("/run/systemd/resolve/resolv.conf");
+        if !upstream.is_empty() {
+            return upstream;
         }
+    }
+
+    servers
+}
+
+// nl80211 over generic netlink, the interface iw and NetworkManager use
+#[cfg(target_os = "linux")]
+mod nl80211 {
+    use std::io;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+    const NLM_F_REQUEST: u16 = 0x1;
+    const NLM_F_ACK: u16 = 0x4;
+    const NLM_F_DUMP: u16 = 0x300;
+    const NLMSG_ERROR: u16 = 0x2;
+    const NLMSG_DONE: u16 = 0x3;
+
+    const GENL_ID_CTRL: u16 = 0x10;
+    const CTRL_CMD_GETFAMILY: u8 = 3;
+    const CTRL_ATTR_FAMILY_ID: u16 = 1;
+    const CTRL_ATTR_FAMILY_NAME: u16 = 2;
+
+    const NL80211_CMD_GET_INTERFACE: u8 = 5;
+    const NL80211_CMD_GET_STATION: u8 = 17;
+    const NL80211_CMD_GET_SCAN: u8 = 32;
+    const NL80211_ATTR_IFINDEX: u16 = 3;
+    const NL80211_ATTR_MAC: u16 = 6;
+    const NL80211_ATTR_STA_INFO: u16 = 21;
+    const NL80211_ATTR_WIPHY_FREQ: u16 = 38;
+    const NL80211_ATTR_BSS: u16 = 47;
+    const NL80211_ATTR_SSID: u16 = 52;
+    const NL80211_ATTR_CHANNEL_WIDTH: u16 = 159;
+    const NL80211_STA_INFO_SIGNAL: u16 = 7;
+    const NL80211_STA_INFO_TX_BITRATE: u16 = 8;
+    const NL80211_RATE_INFO_BITRATE: u16 = 1;
+    const NL80211_RATE_INFO_BITRATE32: u16 = 5;
+    const NL80211_BSS_BSSID: u16 = 1;
+    const NL80211_BSS_FREQUENCY: u16 = 2;
+    const NL80211_BSS_CAPABILITY: u16 = 5;
+    const NL80211_BSS_INFORMATION_ELEMENTS: u16 = 6;
+    const NL80211_BSS_STATUS: u16 = 9;
+    const NL80211_BSS_STATUS_ASSOCIATED: u32 = 1;
+
+    // Channel widths in MHz by enum nl80211_chan_width
+    const CHANNEL_WIDTHS: &[u32] = &[20, 20, 40, 80, 160, 160, 5, 10, 1, 2, 4, 8, 16, 320];
+
+    // Authentication and key management suites in the RSN element, by their 00-0F-AC type
+    const AKM_SUITES: phf::Map<u8, &'static str> = phf::phf_map! {
+        1u8 => "WPA2-Enterprise",
+        2u8 => "WPA2-Personal",
+        3u8 => "WPA2-Enterprise",
+        4u8 => "WPA2-Personal",
+        5u8 => "WPA2-Enterprise",
+        6u8 => "WPA2-Personal",
+        8u8 => "WPA3-Personal",
+        9u8 => "WPA3-Personal",
+        11u8 => "WPA3-Enterprise",
+        12u8 => "WPA3-Enterprise",
+        18u8 => "OWE",
+        24u8 => "WPA3-Personal",
     };
 
-    // Find the first active interface with an IP
-    for iface in interfaces {
-        // println!("[DEBUG] Checking interface: {}", iface.name);
-
-        if iface.is_up() && !iface.ips.is_empty() && !iface.mac.unwrap().is_zero() {
-            // println!("[DEBUG] Found active interface: {}", iface.name);
-
-            // Found the primary interface
-            for ip in iface.ips {
-                if ip.is_ipv4() {
-                    // println!("[DEBUG] Found IPv4 address: {}", ip.ip());
-                    net_info.ip = ip.ip().to_string();
-                    net_info.iface = iface.name.clone();
-                    net_info.mac = iface.mac.unwrap_or_default().to_string();
-
-                    // If it's a WiFi interface, try to get SSID and signal
-                    if iface.name.starts_with("wl") {
-                        // println!("[DEBUG] WiFi interface detected, attempting to get SSID");
-
-                        // Try to get SSID
-                        if let Ok(output) =
-                            Command::new("iwgetid").arg("-r").arg(&iface.name).output()
-                        {
-                            if output.status.success() {
-                                if let Ok(ssid) = str::from_utf8(&output.stdout) {
-                                    // println!("[DEBUG] Found SSID: {}", ssid.trim());
-                                    net_info.wifi_ssid = ssid.trim().to_string();
-                                }
-                            } else {
-                                // println!("[DEBUG] Failed to get SSID with iwgetid");
-                            }
-                        }
-
-                        // Try to get signal strength
-                        // println!("[DEBUG] Attempting to get signal strength");
-                        if let Ok(output) = Command::new("iwconfig").arg(&iface.name).output() {
-                            if output.status.success() {
-                                if let Ok(iwconfig_output) = str::from_utf8(&output.stdout) {
-                                    if let Some(signal_idx) = iwconfig_output.find("Signal level=")
-                                    {
-                                        let signal_str = &iwconfig_output[signal_idx + 13..];
-                                        if let Some(end_idx) = signal_str.find(' ') {
-                                            net_info.wifi_signal =
-                                                signal_str[..end_idx].to_string();
-                                            /* println!(
-                                                "[DEBUG] Found signal strength: {}",
-                                                net_info.wifi_signal
-                                            );
-                                            */
-                                        }
-                                    }
-                                }
-                            } else {
-                                // println!("[DEBUG] Failed to get signal strength with iwconfig");
-                            }
-                        }
+    #[derive(Default)]
+    pub struct Link {
+        pub ssid: Option<String>,
+        pub bssid: Option<String>,
+        pub signal: Option<i32>,    // dBm
+        pub frequency: Option<u32>, // MHz
+        pub width: Option<u32>,     // MHz
+        pub bitrate: Option<u32>,   // 100 kbit/s
+        pub security: Option<String>,
+    }
+
+    fn align(length: usize) -> usize {
+        (length + 3) & !3
+    }
+
+    fn read_u16(data: &[u8]) -> Option<u16> {
+        Some(u16::from_ne_bytes(data.get(..2)?.try_into().ok()?))
+    }
+
+    fn read_u32(data: &[u8]) -> Option<u32> {
+        Some(u32::from_ne_bytes(data.get(..4)?.try_into().ok()?))
+    }
+
+    // Attributes as (type, payload), nested ones are parsed again from their payload
+    fn parse_attrs(mut data: &[u8]) -> Vec<(u16, &[u8])> {
+        let mut attrs = Vec::new();
+
+        while data.len() >= 4 {
+            let length = read_u16(data).unwrap_or(0) as usize;
+            let kind = read_u16(&data[2..]).unwrap_or(0) & 0x3fff; // Without the nested flag
+            if length < 4 || length > data.len() {
+                break;
+            }
+
+            attrs.push((kind, &data[4..length]));
+            data = &data[align(length).min(data.len())..];
+        }
+
+        attrs
+    }
+
+    fn get_attr(data: &[u8], kind: u16) -> Option<&[u8]> {
+        parse_attrs(data)
+            .into_iter()
+            .find(|(k, _)| *k == kind)
+            .map(|(_, value)| value)
+    }
+
+    fn push_attr(message: &mut Vec<u8>, kind: u16, value: &[u8]) {
+        message.extend_from_slice(&((4 + value.len()) as u16).to_ne_bytes());
+        message.extend_from_slice(&kind.to_ne_bytes());
+        message.extend_from_slice(value);
+        message.resize(align(message.len()), 0);
+    }
+
+    // Generic netlink request: nlmsghdr, genlmsghdr and attributes
+    fn build_request(family: u16, command: u8, flags: u16, attrs: &[(u16, &[u8])]) -> Vec<u8> {
+        let mut message = vec![0u8; 16];
+        message.extend_from_slice(&[command, 1, 0, 0]);
+        for (kind, value) in attrs {
+            push_attr(&mut message, *kind, value);
+        }
+
+        let length = message.len() as u32;
+        message[0..4].copy_from_slice(&length.to_ne_bytes());
+        message[4..6].copy_from_slice(&family.to_ne_bytes());
+        message[6..8].copy_from_slice(&(NLM_F_REQUEST | NLM_F_ACK | flags).to_ne_bytes());
+        message
+    }
+
+    // Split a received datagram into the attribute payloads of its messages,
+    // returning true once the request is complete (done message or ack)
+    fn parse_responses(data: &[u8], payloads: &mut Vec<Vec<u8>>) -> io::Result<bool> {
+        let mut data = data;
+
+        while data.len() >= 16 {
+            let length = read_u32(data).unwrap_or(0) as usize;
+            let kind = read_u16(&data[4..]).unwrap_or(0);
+            if length < 16 || length > data.len() {
+                break;
+            }
+
+            match kind {
+                NLMSG_DONE => return Ok(true),
+                NLMSG_ERROR => {
+                    // An error code of 0 acknowledges the request
+                    let code = read_u32(&data[16..]).unwrap_or(0) as i32;
+                    return match code {
+                        0 => Ok(true),
+                        _ => Err(io::Error::from_raw_os_error(-code)),
+                    };
+                }
+                _ => {
+                    if let Some(payload) = data.get(20..length) {
+                        payloads.push(payload.to_vec());
                     }
+                }
+            }
 
-                    // If we found an interface with IP, use it and finish
-                    return net_info;
+            data = &data[align(length).min(data.len())..];
+        }
+
+        Ok(false)
+    }
+
+    struct Socket(OwnedFd);
+
+    impl Socket {
+        fn open() -> io::Result<Socket> {
+            let fd = unsafe {
+                libc::socket(
+                    libc::AF_NETLINK,
+                    libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+                    libc::NETLINK_GENERIC,
+                )
+            };
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let socket = Socket(unsafe { OwnedFd::from_raw_fd(fd) });
+
+            // Never hang the whole program on an unresponsive kernel
+            let timeout = libc::timeval {
+                tv_sec: 0,
+                tv_usec: 500_000,
+            };
+            unsafe {
+                libc::setsockopt(
+                    fd,
+                    libc::SOL_SOCKET,
+                    libc::SO_RCVTIMEO,
+                    &timeout as *const _ as *const libc::c_void,
+                    std::mem::size_of::<libc::timeval>() as libc::socklen_t,
+                )
+            };
+
+            Ok(socket)
+        }
+
+        fn request(
+            &self,
+            family: u16,
+            command: u8,
+            flags: u16,
+            attrs: &[(u16, &[u8])],
+        ) -> io::Result<Vec<Vec<u8>>> {
+            let message = build_request(family, command, flags, attrs);
+
+            let mut address: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+            address.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+            let sent = unsafe {
+                libc::sendto(
+                    self.0.as_raw_fd(),
+                    message.as_ptr() as *const libc::c_void,
+                    message.len(),
+                    0,
+                    &address as *const _ as *const libc::sockaddr,
+                    std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+                )
+            };
+            if sent < 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            let mut payloads = Vec::new();
+            let mut buffer = vec![0u8; 65536];
+            loop {
+                let received = unsafe {
+                    libc::recv(
+                        self.0.as_raw_fd(),
+                        buffer.as_mut_ptr() as *mut libc::c_void,
+                        buffer.len(),
+                        0,
+                    )
+                };
+                if received < 0 {
+                    return Err(io::Error::last_os_error());
+                }
+
+                if parse_responses(&buffer[..received as usize], &mut payloads)? {
+                    return Ok(payloads);
                 }
             }
         }
     }
 
-    // println!("[DEBUG] No suitable network interface found");
+    // "WPA2-Personal" from the RSN element, "WPA" or "WEP" for older networks
+    fn parse_security(elements: &[u8], capability: u16) -> String {
+        let mut rsn = None;
+        let mut wpa = false;
+
+        let mut data = elements;
+        while data.len() >= 2 {
+            let (id, length) = (data[0], data[1] as usize);
+            let Some(element) = data.get(2..2 + length) else {
+                break;
+            };
+
+            match id {
+                48 => rsn = Some(element),
+                221 => wpa |= element.starts_with(&[0x00, 0x50, 0xf2, 0x01]),
+                _ => {}
+            }
+            data = &data[2 + length..];
+        }
+
+        if let Some(rsn) = rsn {
+            // Version, group cipher, pairwise ciphers and then the AKM suites, with
+            // little-endian counts unlike the netlink attributes
+            let read_le16 = |data: &[u8]| Some(u16::from_le_bytes(data.get(..2)?.try_into().ok()?));
+            let akms = (|| {
+                let pairwise = read_le16(rsn.get(6..)?)? as usize;
+                let offset = 8 + pairwise * 4;
+                let count = read_le16(rsn.get(offset..)?)? as usize;
+                rsn.get(offset + 2..offset + 2 + count * 4)
+            })()
+            .unwrap_or_default();
+
+            let suites: Vec<&str> = akms
+                .chunks_exact(4)
+                .filter(|suite| suite[..3] == [0x00, 0x0f, 0xac])
+                .filter_map(|suite| AKM_SUITES.get(&suite[3]).copied())
+                .collect();
+
+            // Transition mode networks accept both
+            return match suites.as_slice() {
+                [] => "WPA2".to_string(),
+                _ if suites.contains(&"WPA2-Personal") && suites.contains(&"WPA3-Personal") => {
+                    "WPA2/WPA3-Personal".to_string()
+                }
+                [first, ..] => first.to_string(),
+            };
+        }
+
+        if wpa {
+            "WPA".to_string()
+        } else if capability & 0x10 != 0 {
+            "WEP".to_string()
+        } else {
+            "Open".to_string()
+        }
+    }
+
+    fn format_mac(mac: &[u8]) -> String {
+        mac.iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<Vec<String>>()
+            .join(":")
+    }
+
+    fn parse_interface(link: &mut Link, attrs: &[u8]) {
+        if let Some(ssid) = get_attr(attrs, NL80211_ATTR_SSID) {
+            link.ssid = Some(String::from_utf8_lossy(ssid).to_string());
+        }
+        link.frequency = get_attr(attrs, NL80211_ATTR_WIPHY_FREQ).and_then(read_u32);
+        link.width = get_attr(attrs, NL80211_ATTR_CHANNEL_WIDTH)
+            .and_then(read_u32)
+            .and_then(|width| CHANNEL_WIDTHS.get(width as usize).copied());
+    }
+
+    fn parse_station(link: &mut Link, attrs: &[u8]) {
+        if let Some(mac) = get_attr(attrs, NL80211_ATTR_MAC) {
+            link.bssid = Some(format_mac(mac));
+        }
+
+        let Some(info) = get_attr(attrs, NL80211_ATTR_STA_INFO) else {
+            return;
+        };
+        if let Some(signal) = get_attr(info, NL80211_STA_INFO_SIGNAL).and_then(|s| s.first()) {
+            link.signal = Some(*signal as i8 as i32);
+        }
+
+        // The 16 bit rate overflows above 6.5 Gbit/s
+        if let Some(rate) = get_attr(info, NL80211_STA_INFO_TX_BITRATE) {
+            link.bitrate = get_attr(rate, NL80211_RATE_INFO_BITRATE32)
+                .and_then(read_u32)
+                .or_else(|| {
+                    get_attr(rate, NL80211_RATE_INFO_BITRATE)
+                        .and_then(|r| read_u16(r).map(u32::from))
+                });
+        }
+    }
+
+    // Scan results include the associated access point, with its SSID and security
+    fn parse_bss(link: &mut Link, attrs: &[u8]) -> bool {
+        let Some(bss) = get_attr(attrs, NL80211_ATTR_BSS) else {
+            return false;
+        };
+        if get_attr(bss, NL80211_BSS_STATUS).and_then(read_u32)
+            != Some(NL80211_BSS_STATUS_ASSOCIATED)
+        {
+            return false;
+        }
+
+        let elements = get_attr(bss, NL80211_BSS_INFORMATION_ELEMENTS).unwrap_or_default();
+        let capability = get_attr(bss, NL80211_BSS_CAPABILITY)
+            .and_then(read_u16)
+            .unwrap_or(0);
+        link.security = Some(parse_security(elements, capability));
+
+        if link.bssid.is_none() {
+            link.bssid = get_attr(bss, NL80211_BSS_BSSID).map(format_mac);
+        }
+        if link.frequency.is_none() {
+            link.frequency = get_attr(bss, NL80211_BSS_FREQUENCY).and_then(read_u32);
+        }
+
+        // The SSID element, for kernels that leave it out of the interface
+        if link.ssid.is_none() && elements.first() == Some(&0) {
+            let length = *elements.get(1).unwrap_or(&0) as usize;
+            if let Some(ssid) = elements.get(2..2 + length) {
+                link.ssid = Some(String::from_utf8_lossy(ssid).to_string());
+            }
+        }
+
+        true
+    }
+
+    // Connection state of a wireless interface by index
+    pub fn get_link(ifindex: u32) -> Option<Link> {
+        let socket = Socket::open().ok()?;
+
+        let family = socket
+            .request(
+                GENL_ID_CTRL,
+                CTRL_CMD_GETFAMILY,
+                0,
+                &[(CTRL_ATTR_FAMILY_NAME, b"nl80211\0")],
+            )
+            .ok()?
+            .iter()
+            .find_map(|attrs| get_attr(attrs, CTRL_ATTR_FAMILY_ID).and_then(read_u16))?;
+
+        let index = ifindex.to_ne_bytes();
+        let attrs = [(NL80211_ATTR_IFINDEX, index.as_slice())];
+        let mut link = Link::default();
+
+        for payload in socket
+            .request(family, NL80211_CMD_GET_INTERFACE, 0, &attrs)
+            .ok()?
+        {
+            parse_interface(&mut link, &payload);
+        }
+
+        // Stations of a managed interface are the access point it is associated with
+        for payload in socket
+            .request(family, NL80211_CMD_GET_STATION, NLM_F_DUMP, &attrs)
+            .unwrap_or_default()
+        {
+            parse_station(&mut link, &payload);
+        }
+
+        for payload in socket
+            .request(family, NL80211_CMD_GET_SCAN, NLM_F_DUMP, &attrs)
+            .unwrap_or_default()
+        {
+            if parse_bss(&mut link, &payload) {
+                break;
+            }
+        }
+
+        Some(link)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const FAMILY: u16 = 0x1c;
+        const NLM_F_MULTI: u16 = 0x2;
+        const NLA_F_NESTED: u16 = 0x8000;
+
+        fn attr(kind: u16, value: &[u8]) -> Vec<u8> {
+            let mut data = Vec::new();
+            push_attr(&mut data, kind, value);
+            data
+        }
+
+        // nlmsghdr (length, type, flags, sequence, port) and the payload
+        fn message(kind: u16, flags: u16, payload: &[u8]) -> Vec<u8> {
+            let mut data = ((16 + payload.len()) as u32).to_ne_bytes().to_vec();
+            data.extend_from_slice(&kind.to_ne_bytes());
+            data.extend_from_slice(&flags.to_ne_bytes());
+            data.extend_from_slice(&[0; 8]);
+            data.extend_from_slice(payload);
+            data.resize(align(data.len()), 0);
+            data
+        }
+
+        // genlmsghdr (command, version) and the attributes
+        fn genl(command: u8, attrs: &[Vec<u8>]) -> Vec<u8> {
+            let mut data = vec![command, 1, 0, 0];
+            attrs.iter().for_each(|attr| data.extend_from_slice(attr));
+            data
+        }
+
+        fn error(code: i32) -> Vec<u8> {
+            let mut payload = code.to_ne_bytes().to_vec();
+            payload.extend_from_slice(&[0; 16]); // Header of the failed request
+            message(NLMSG_ERROR, 0, &payload)
+        }
+
+        // RSN element with CCMP ciphers and the given 00-0F-AC AKM suites
+        fn rsn(akms: &[u8]) -> Vec<u8> {
+            let mut body = vec![1, 0, 0x00, 0x0f, 0xac, 4, 1, 0, 0x00, 0x0f, 0xac, 4];
+            body.extend_from_slice(&(akms.len() as u16).to_le_bytes());
+            for akm in akms {
+                body.extend_from_slice(&[0x00, 0x0f, 0xac, *akm]);
+            }
+            body.extend_from_slice(&[0, 0]); // RSN capabilities
+
+            let mut element = vec![48, body.len() as u8];
+            element.extend(body);
+            element
+        }
+
+        #[test]
+        fn request() {
+            let index = 3u32.to_ne_bytes();
+            let request = build_request(
+                FAMILY,
+                NL80211_CMD_GET_STATION,
+                NLM_F_DUMP,
+                &[(NL80211_ATTR_IFINDEX, &index)],
+            );
+
+            assert_eq!(read_u32(&request), Some(request.len() as u32));
+            assert_eq!(read_u16(&request[4..]), Some(FAMILY));
+            assert_eq!(read_u16(&request[6..]), Some(0x305));
+            assert_eq!(request[16..18], [NL80211_CMD_GET_STATION, 1]);
+            assert_eq!(
+                parse_attrs(&request[20..]),
+                [(NL80211_ATTR_IFINDEX, &index[..])]
+            );
+        }
+
+        #[test]
+        fn family_reply() {
+            let reply = genl(
+                1,
+                &[
+                    attr(CTRL_ATTR_FAMILY_ID, &FAMILY.to_ne_bytes()),
+                    attr(CTRL_ATTR_FAMILY_NAME, b"nl80211\0"),
+                ],
+            );
+            let mut datagram = message(GENL_ID_CTRL, 0, &reply);
+            datagram.extend(error(0));
+
+            let mut payloads = Vec::new();
+            assert!(parse_responses(&datagram, &mut payloads).unwrap());
+            assert_eq!(payloads.len(), 1);
+            assert_eq!(
+                get_attr(&payloads[0], CTRL_ATTR_FAMILY_ID).and_then(read_u16),
+                Some(FAMILY)
+            );
+            assert_eq!(
+                get_attr(&payloads[0], CTRL_ATTR_FAMILY_NAME),
+                Some(&b"nl80211\0"[..])
+            );
+        }
+
+        // A dump spread over two datagrams, finished by NLMSG_DONE
+        #[test]
+        fn multipart_dump() {
+            let station = |mac: u8| {
+                let payload = genl(
+                    NL80211_CMD_GET_STATION,
+                    &[attr(NL80211_ATTR_MAC, &[mac; 6])],
+                );
+                message(FAMILY, NLM_F_MULTI, &payload)
+            };
+
+            let mut payloads = Vec::new();
+            let first = [station(1), station(2)].concat();
+            assert!(!parse_responses(&first, &mut payloads).unwrap());
+
+            let second = [station(3), message(NLMSG_DONE, NLM_F_MULTI, &[0; 4])].concat();
+            assert!(parse_responses(&second, &mut payloads).unwrap());
+
+            let macs: Vec<u8> = payloads
+                .iter()
+                .filter_map(|payload| get_attr(payload, NL80211_ATTR_MAC))
+                .map(|mac| mac[0])
+                .collect();
+            assert_eq!(macs, [1, 2, 3]);
+        }
+
+        #[test]
+        fn error_reply() {
+            let mut payloads = Vec::new();
+            let error = parse_responses(&error(-libc::ENODEV), &mut payloads).unwrap_err();
+            assert_eq!(error.raw_os_error(), Some(libc::ENODEV));
+            assert!(payloads.is_empty());
+        }
+
+        #[test]
+        fn truncated_messages() {
+            let reply = message(FAMILY, 0, &genl(NL80211_CMD_GET_INTERFACE, &[]));
+
+            // Shorter than its header says, or than a header at all
+            let mut payloads = Vec::new();
+            assert!(!parse_responses(&reply[..reply.len() - 2], &mut payloads).unwrap());
+            assert!(!parse_responses(&reply[..10], &mut payloads).unwrap());
+            assert!(payloads.is_empty());
+
+            // A length below the header size would never advance
+            let mut bogus = reply.clone();
+            bogus[..4].copy_from_slice(&8u32.to_ne_bytes());
+            assert!(!parse_responses(&bogus, &mut payloads).unwrap());
+        }
+
+        #[test]
+        fn truncated_attributes() {
+            let ssid = attr(NL80211_ATTR_SSID, b"home");
+
+            // Length past the end of the buffer
+            let mut data = ssid.clone();
+            data.extend_from_slice(&attr(NL80211_ATTR_WIPHY_FREQ, &2412u32.to_ne_bytes())[..6]);
+            assert_eq!(parse_attrs(&data), [(NL80211_ATTR_SSID, &b"home"[..])]);
+
+            // Length below the attribute header
+            let mut data = ssid.clone();
+            data.extend_from_slice(&[2, 0, 1, 0]);
+            data.extend_from_slice(&ssid);
+            assert_eq!(parse_attrs(&data).len(), 1);
+
+            // The last attribute may leave out its padding
+            let mut data = ssid.clone();
+            data.extend_from_slice(&attr(NL80211_ATTR_SSID, b"x")[..5]);
+            assert_eq!(parse_attrs(&data)[1], (NL80211_ATTR_SSID, &b"x"[..]));
+
+            // Values too short for their type
+            assert_eq!(read_u32(&[1, 2, 3]), None);
+            assert_eq!(read_u16(&[1]), None);
+        }
+
+        #[test]
+        fn interface_reply() {
+            let payload = [
+                attr(NL80211_ATTR_IFINDEX, &3u32.to_ne_bytes()),
+                attr(NL80211_ATTR_SSID, b"home"),
+                attr(NL80211_ATTR_WIPHY_FREQ, &5180u32.to_ne_bytes()),
+                attr(NL80211_ATTR_CHANNEL_WIDTH, &3u32.to_ne_bytes()),
+            ]
+            .concat();
+
+            let mut link = Link::default();
+            parse_interface(&mut link, &payload);
+            assert_eq!(link.ssid.as_deref(), Some("home"));
+            assert_eq!(link.frequency, Some(5180));
+            assert_eq!(link.width, Some(80));
+        }
+
+        #[test]
+        fn station_reply() {
+            let station = |rate: Vec<u8>| {
+                let info = [
+                    attr(NL80211_STA_INFO_SIGNAL, &[-52i8 as u8]),
+                    attr(NL80211_STA_INFO_TX_BITRATE | NLA_F_NESTED, &rate),
+                ]
+                .concat();
+                [
+                    attr(NL80211_ATTR_MAC, &[0x02, 0x11, 0x22, 0x33, 0x44, 0x55]),
+                    attr(NL80211_ATTR_STA_INFO | NLA_F_NESTED, &info),
+                ]
+                .concat()
+            };
+
+            let mut link = Link::default();
+            parse_station(
+                &mut link,
+                &station(attr(NL80211_RATE_INFO_BITRATE32, &72060u32.to_ne_bytes())),
+            );
+            assert_eq!(link.bssid.as_deref(), Some("02:11:22:33:44:55"));
+            assert_eq!(link.signal, Some(-52));
+            assert_eq!(link.bitrate, Some(72060));
+
+            // Older kernels only have the 16 bit rate
+            let mut link = Link::default();
+            parse_station(
+                &mut link,
+                &station(attr(NL80211_RATE_INFO_BITRATE, &8667u16.to_ne_bytes())),
+            );
+            assert_eq!(link.bitrate, Some(8667));
+        }
+
+        #[test]
+        fn scan_dump() {
+            let bss = |status: u32, elements: &[u8]| {
+                let bss = [
+                    attr(NL80211_BSS_BSSID, &[0x02, 0xaa, 0xbb, 0xcc, 0xdd, 0xee]),
+                    attr(NL80211_BSS_FREQUENCY, &2437u32.to_ne_bytes()),
+                    attr(NL80211_BSS_CAPABILITY, &0x0411u16.to_ne_bytes()),
+                    attr(NL80211_BSS_INFORMATION_ELEMENTS, elements),
+                    attr(NL80211_BSS_STATUS, &status.to_ne_bytes()),
+                ]
+                .concat();
+                attr(NL80211_ATTR_BSS | NLA_F_NESTED, &bss)
+            };
+            let elements = [vec![0, 4], b"cafe".to_vec(), rsn(&[8])].concat();
+
+            // Networks in range that we are not associated with are skipped
+            let mut link = Link::default();
+            assert!(!parse_bss(&mut link, &bss(0, &elements)));
+            assert!(link.security.is_none());
+
+            assert!(parse_bss(
+                &mut link,
+                &bss(NL80211_BSS_STATUS_ASSOCIATED, &elements)
+            ));
+            assert_eq!(link.ssid.as_deref(), Some("cafe"));
+            assert_eq!(link.bssid.as_deref(), Some("02:aa:bb:cc:dd:ee"));
+            assert_eq!(link.frequency, Some(2437));
+            assert_eq!(link.security.as_deref(), Some("WPA3-Personal"));
+
+            // The vendor specific WPA element of pre-RSN networks
+            let wpa = [221, 8, 0x00, 0x50, 0xf2, 0x01, 1, 0, 0, 0];
+            let mut link = Link::default();
+            assert!(parse_bss(
+                &mut link,
+                &bss(NL80211_BSS_STATUS_ASSOCIATED, &wpa)
+            ));
+            assert_eq!(link.security.as_deref(), Some("WPA"));
+        }
+
+        #[test]
+        fn akm_suites() {
+            for (suite, name) in AKM_SUITES.entries() {
+                assert_eq!(parse_security(&rsn(&[*suite]), 0), *name, "AKM {}", suite);
+            }
+
+            assert_eq!(parse_security(&rsn(&[9]), 0), "WPA3-Personal");
+            assert_eq!(parse_security(&rsn(&[2, 8]), 0), "WPA2/WPA3-Personal");
+            assert_eq!(parse_security(&rsn(&[8, 4]), 0), "WPA2/WPA3-Personal");
+            assert_eq!(parse_security(&rsn(&[1, 2]), 0), "WPA2-Enterprise");
+            assert_eq!(parse_security(&rsn(&[200]), 0), "WPA2");
+            assert_eq!(parse_security(&rsn(&[]), 0), "WPA2");
+        }
+
+        #[test]
+        fn legacy_security() {
+            assert_eq!(parse_security(&[], 0x0010), "WEP");
+            assert_eq!(parse_security(&[], 0x0001), "Open");
+
+            // An element running past the end is ignored
+            assert_eq!(parse_security(&[48, 40, 1, 0], 0), "Open");
+
+            // RSN elements that end before the AKM list
+            let mut short = rsn(&[2])[..10].to_vec();
+            short[1] = 8;
+            assert_eq!(parse_security(&short, 0), "WPA2");
+        }
+    }
+}
+
+// Signal quality as NetworkManager reports it, -100 dBm is 0% and -50 dBm or more is 100%
+#[cfg(target_os = "linux")]
+fn get_quality(signal: i32) -> u32 {
+    (2 * (signal + 100)).clamp(0, 100) as u32
+}
+
+// IEEE 802.11 channel number of a frequency in MHz
+#[cfg(target_os = "linux")]
+fn get_channel(frequency: u32) -> Option<u32> {
+    match frequency {
+        2484 => Some(14),
+        2412..=2472 => Some((frequency - 2407) / 5),
+        5160..=5885 => Some((frequency - 5000) / 5),
+        5935 => Some(2),
+        5955..=7115 => Some((frequency - 5950) / 5),
+        58320..=70200 => Some((frequency - 56160) / 2160),
+        _ => None,
+    }
+}
+
+fn get_band(frequency: u32) -> &'static str {
+    match frequency {
+        2400..=2500 => "2.4 GHz",
+        4900..=5920 => "5 GHz",
+        5925..=7125 => "6 GHz",
+        57000..=71000 => "60 GHz",
+        _ => "Unknown",
+    }
+}
+
+// Wireless interfaces have a wireless directory (or phy80211 link) in sysfs
+#[cfg(target_os = "linux")]
+fn is_wireless(iface: &str) -> bool {
+    use std::path::Path;
+
+    let path = Path::new("/sys/class/net").join(iface);
+    path.join("wireless").exists() || path.join("phy80211").exists()
+}
+
+// Kind of interface from its ARP hardware type, DEVTYPE in uevent and sysfs entries
+#[cfg(target_os = "linux")]
+fn get_kind(iface: &Interface) -> &'static str {
+    use crate::utils::parse_key_values;
+    use std::path::Path;
+
+    let path = Path::new("/sys/class/net").join(&iface.name);
+    let read = |file: &str| read_sysfs(&iface.name, file);
+
+    // ARPHRD_* values from if_arp.h
+    let arp_type: u32 = read("type")
+        .and_then(|arp_type| arp_type.parse().ok())
+        .unwrap_or(0);
+    if iface.loopback || arp_type == 772 {
+        return "loopback";
+    }
+
+    // DEVTYPE=bridge, vlan, bond, wireguard or wlan
+    let uevent = read("uevent")
+        .map(|uevent| parse_key_values(&uevent))
+        .unwrap_or_default();
+    match uevent.get("DEVTYPE").map(String::as_str) {
+        Some("wlan") => return "wireless",
+        Some("bridge") => return "bridge",
+        Some("vlan") => return "vlan",
+        Some("bond") => return "bond",
+        Some("wireguard") => return "wireguard",
+        _ => {}
+    }
+
+    if is_wireless(&iface.name) {
+        "wireless"
+    } else if path.join("bridge").exists() {
+        "bridge"
+    } else if path.join("bonding").exists() {
+        "bond"
+    } else if let Some(flags) = read("tun_flags") {
+        // IFF_TAP, e.g. "0x1002"
+        let flags = u32::from_str_radix(flags.trim_start_matches("0x"), 16).unwrap_or(0);
+        if flags & 0x2 != 0 {
+            "tap"
+        } else {
+            "tun"
+        }
+    } else if arp_type == 512 {
+        // L2TP sessions list their PPP interface as "   interface ppp0"
+        let l2tp = std::fs::read_to_string("/proc/net/pppol2tp").unwrap_or_default();
+        match l2tp
+            .lines()
+            .any(|line| line.trim().strip_prefix("interface ") == Some(iface.name.as_str()))
+        {
+            true => "l2tp",
+            false => "ppp",
+        }
+    } else if arp_type == 65534 {
+        "tun"
+    } else if path.join("device").exists() {
+        "ethernet"
+    } else if read("iflink") != read("ifindex") {
+        // A veth is linked to its peer, usually in another network namespace
+        "veth"
+    } else {
+        "virtual"
+    }
+}
+
+// Whether a bridge, bond or VLAN sits on a physical interface, following the
+// lower_<name> links through stacked ones (e.g. a bridge on a VLAN on eth0)
+#[cfg(target_os = "linux")]
+fn has_physical_lower(name: &str, depth: usize) -> bool {
+    use std::fs;
+    use std::path::Path;
+
+    if depth > 8 {
+        return false;
+    }
+
+    fs::read_dir(Path::new("/sys/class/net").join(name))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            file_name.strip_prefix("lower_").map(str::to_string)
+        })
+        .any(|lower| {
+            Path::new("/sys/class/net")
+                .join(&lower)
+                .join("device")
+                .exists()
+                || has_physical_lower(&lower, depth + 1)
+        })
+}
+
+#[cfg(target_os = "linux")]
+fn get_wifi(iface: &Interface) -> Option<WifiInfo> {
+    use std::fs;
+
+    let mut wifi = WifiInfo {
+        iface: iface.name.clone(),
+        ..Default::default()
+    };
+
+    if let Some(link) = nl80211::get_link(iface.index) {
+        wifi.ssid = link.ssid;
+        wifi.bssid = link.bssid;
+        wifi.signal = link.signal;
+        wifi.frequency = link.frequency;
+        wifi.channel = link.frequency.and_then(get_channel);
+        wifi.width = link.width;
+        wifi.bitrate = link.bitrate.map(|bitrate| bitrate * 100);
+        wifi.security = link.security;
+    }
+
+    // Without nl80211 the signal level is still in /proc/net/wireless:
+    // "wlan0: 0000   54.  -56.  -256        0      0      0      0      0        0"
+    if wifi.signal.is_none() {
+        wifi.signal = fs::read_to_string("/proc/net/wireless")
+            .ok()
+            .and_then(|wireless| {
+                wireless
+                    .lines()
+                    .filter_map(|line| line.split_once(':'))
+                    .find(|(name, _)| name.trim() == iface.name)
+                    .and_then(|(_, values)| {
+                        let level = values.split_whitespace().nth(2)?;
+                        level.trim_end_matches('.').parse::<i32>().ok()
+                    })
+            })
+            .filter(|level| (-200..0).contains(level));
+    }
+    wifi.quality = wifi.signal.map(get_quality);
+
+    Some(wifi)
+}
+
+#[cfg(target_os = "linux")]
+fn read_sysfs(iface: &str, file: &str) -> Option<String> {
+    let value = std::fs::read_to_string(format!("/sys/class/net/{}/{}", iface, file)).ok()?;
+    Some(value.trim().to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn get_counters(iface: &Interface) -> Option<Counters> {
+    let read = |name: &str| {
+        read_sysfs(&iface.name, &format!("statistics/{}", name))?
+            .parse()
+            .ok()
+    };
+
+    Some(Counters {
+        rx_bytes: read("rx_bytes")?,
+        tx_bytes: read("tx_bytes")?,
+        rx_packets: read("rx_packets")?,
+        tx_packets: read("tx_packets")?,
+    })
+}
+
+// Speed and duplex can't be read while the link is down, and are -1 or unknown
+// on virtual interfaces
+#[cfg(target_os = "linux")]
+fn get_link_info(iface: &mut Interface) {
+    iface.counters = get_counters(iface);
+    iface.speed = read_sysfs(&iface.name, "speed")
+        .and_then(|speed| speed.parse::<i64>().ok())
+        .filter(|speed| *speed > 0)
+        .map(|speed| speed as u64);
+    iface.duplex = read_sysfs(&iface.name, "duplex").filter(|duplex| duplex != "unknown");
+    iface.mtu = read_sysfs(&iface.name, "mtu").and_then(|mtu| mtu.parse().ok());
+    iface.operstate = read_sysfs(&iface.name, "operstate");
+
+    // /sys/class/net/eth0/device/driver -> ../../../bus/pci/drivers/e1000e
+    iface.driver = std::fs::read_link(format!("/sys/class/net/{}/device/driver", iface.name))
+        .ok()
+        .and_then(|driver| {
+            driver
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        });
+}
+
+#[cfg(target_os = "linux")]
+fn get_net(config: &NetConfig) -> NetInfo {
+    use pnet::datalink;
+
+    let mut net_info = NetInfo {
+        dns: get_dns(),
+        ..Default::default()
+    };
+
+    net_info.interfaces = datalink::interfaces()
+        .into_iter()
+        .map(|iface| {
+            let address = |ip: &pnet::ipnetwork::IpNetwork| format!("{}/{}", ip.ip(), ip.prefix());
+
+            Interface {
+                index: iface.index,
+                mac: iface
+                    .mac
+                    .filter(|mac| !mac.is_zero())
+                    .map(|mac| mac.to_string()),
+                up: iface.is_up(),
+                loopback: iface.is_loopback(),
+                ipv4: iface
+                    .ips
+                    .iter()
+                    .filter(|ip| ip.is_ipv4())
+                    .map(address)
+                    .collect(),
+                ipv6: iface
+                    .ips
+                    .iter()
+                    .filter(|ip| ip.is_ipv6())
+                    .map(address)
+                    .collect(),
+                name: iface.name,
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    for iface in net_info.interfaces.iter_mut() {
+        get_link_info(iface);
+        iface.kind = get_kind(iface);
+        iface.physical_lower =
+            matches!(iface.kind, "bridge" | "bond" | "vlan") && has_physical_lower(&iface.name, 0);
+    }
+
+    // Default routes through virtual interfaces (e.g. a full tunnel VPN) can be skipped
+    let allowed = |name: &str| {
+        !config.exclude_virtual
+            || net_info
+                .interfaces
+                .iter()
+                .find(|iface| iface.name == name)
+                .is_some_and(|iface| !is_virtual(iface.kind) || iface.physical_lower)
+    };
+
+    let route = get_default_routes()
+        .into_iter()
+        .find(|(iface, _)| allowed(iface));
+    let route6 = get_default_routes6()
+        .into_iter()
+        .find(|(iface, _)| allowed(iface));
+
+    if let Some((_, gateway)) = &route {
+        net_info.gateway = gateway.clone();
+    }
+    if let Some((_, gateway)) = &route6 {
+        net_info.gateway6 = gateway.clone();
+    }
+
+    // Without a default route, fall back to the first interface with an address
+    let primary = route.or(route6).map(|(iface, _)| iface).or_else(|| {
+        net_info
+            .interfaces
+            .iter()
+            .find(|iface| {
+                iface.up && !iface.loopback && !iface.ipv4.is_empty() && allowed(&iface.name)
+            })
+            .map(|iface| iface.name.clone())
+    });
+
+    if let Some(iface) = primary {
+        net_info.iface = iface;
+    }
+
+    let wireless = net_info
+        .interfaces
+        .iter()
+        .filter(|iface| iface.up && iface.kind == "wireless")
+        .min_by_key(|iface| iface.name != net_info.iface);
+    net_info.wifi = wireless.and_then(get_wifi);
+
     net_info
 }
 
+// IP address of a SOCKET_ADDRESS, for unicast, gateway and DNS server entries
+#[cfg(target_os = "windows")]
+unsafe fn get_socket_ip(
+    address: &windows::Win32::Networking::WinSock::SOCKET_ADDRESS,
+) -> Option<std::net::IpAddr> {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+    use windows::Win32::Networking::WinSock::{AF_INET, AF_INET6, SOCKADDR_IN, SOCKADDR_IN6};
+
+    let socket_addr = address.lpSockaddr;
+    if socket_addr.is_null() {
+        return None;
+    }
+
+    if (*socket_addr).sa_family == AF_INET {
+        let ipv4_addr = &*(socket_addr as *const SOCKADDR_IN);
+        Some(IpAddr::V4(Ipv4Addr::from(
+            ipv4_addr.sin_addr.S_un.S_addr.to_ne_bytes(),
+        )))
+    } else if (*socket_addr).sa_family == AF_INET6 {
+        let ipv6_addr = &*(socket_addr as *const SOCKADDR_IN6);
+        Some(IpAddr::V6(Ipv6Addr::from(ipv6_addr.sin6_addr.u.Byte)))
+    } else {
+        None
+    }
+}
+
+#[cfg(target_os = "windows")]
+unsafe fn get_wifi(iface: &str) -> Option<WifiInfo> {
+    use windows::Win32::Foundation::ERROR_SUCCESS;
+    use windows::Win32::NetworkManagement::WiFi::{
+        wlan_interface_state_connected, wlan_intf_opcode_channel_number,
+        wlan_intf_opcode_current_connection, WlanCloseHandle, WlanEnumInterfaces, WlanFreeMemory,
+        WlanOpenHandle, WlanQueryInterface, WLAN_API_VERSION_2_0, WLAN_CONNECTION_ATTRIBUTES,
+        WLAN_INTERFACE_INFO_LIST, WLAN_OPCODE_VALUE_TYPE,
+    };
+
+    let mut handle = Default::default();
+    let client_version = WLAN_API_VERSION_2_0;
+    let mut negotiated_version = 0;
+
+    let result = WlanOpenHandle(client_version, None, &mut negotiated_version, &mut handle);
+    if result != ERROR_SUCCESS.0 {
+        return None;
+    }
+
+    let mut wifi = WifiInfo {
+        iface: iface.to_string(),
+        ..Default::default()
+    };
+
+    let mut iface_list_ptr = std::ptr::null_mut();
+    let result = WlanEnumInterfaces(handle, None, &mut iface_list_ptr);
+
+    if result == ERROR_SUCCESS.0 && !iface_list_ptr.is_null() {
+        let iface_list = &*(iface_list_ptr as *const WLAN_INTERFACE_INFO_LIST);
+
+        for i in 0..iface_list.dwNumberOfItems {
+            let iface_info = &iface_list.InterfaceInfo[i as usize];
+            if iface_info.isState != wlan_interface_state_connected {
+                continue;
+            }
+
+            let mut data_size = 0u32;
+            let mut data_ptr = std::ptr::null_mut();
+            let mut opcode_value_type = WLAN_OPCODE_VALUE_TYPE(0);
+
+            let result = WlanQueryInterface(
+                handle,
+                &iface_info.InterfaceGuid,
+                wlan_intf_opcode_current_connection,
+                None,
+                &mut data_size,
+                &mut data_ptr,
+                Some(&mut opcode_value_type),
+            );
+
+            if result == ERROR_SUCCESS.0 && !data_ptr.is_null() {
+                let conn_attr = &*(data_ptr as *const WLAN_CONNECTION_ATTRIBUTES);
+                let association = &conn_attr.wlanAssociationAttributes;
+                let ssid_bytes = &association.dot11Ssid;
+                let ssid =
+                    String::from_utf8_lossy(&ssid_bytes.ucSSID[..ssid_bytes.uSSIDLength as usize]);
+
+                wifi.ssid = Some(ssid.to_string());
+                wifi.bssid = Some(
+                    association
+                        .dot11Bssid
+                        .iter()
+                        .map(|byte| format!("{:02x}", byte))
+                        .collect::<Vec<String>>()
+                        .join(":"),
+                );
+
+                // Windows only reports quality, where 0% is -100 dBm and 100% is -50 dBm
+                let quality = association.wlanSignalQuality.min(100);
+                wifi.quality = Some(quality);
+                wifi.signal = Some(quality as i32 / 2 - 100);
+                wifi.bitrate = Some(association.ulTxRate);
+
+                let security = &conn_attr.wlanSecurityAttributes;
+                wifi.security = Some(
+                    match (
+                        security.dot11AuthAlgorithm.0,
+                        security.bSecurityEnabled.as_bool(),
+                    ) {
+                        (1, false) => "Open",
+                        (1, true) | (2, _) => "WEP",
+                        (3, _) | (4, _) => "WPA",
+                        (6, _) => "WPA2-Enterprise",
+                        (7, _) => "WPA2-Personal",
+                        (8, _) | (11, _) => "WPA3-Enterprise",
+                        (9, _) => "WPA3-Personal",
+                        (10, _) => "OWE",
+                        _ => "Unknown",
+                    }
+                    .to_string(),
+                );
+
+                WlanFreeMemory(data_ptr);
+            }
+
+            let mut data_ptr = std::ptr::null_mut();
+            let result = WlanQueryInterface(
+                handle,
+                &iface_info.InterfaceGuid,
+                wlan_intf_opcode_channel_number,
+                None,
+                &mut data_size,
+                &mut data_ptr,
+                Some(&mut opcode_value_type),
+            );
+
+            if result == ERROR_SUCCESS.0 && !data_ptr.is_null() {
+                wifi.channel = Some(*(data_ptr as *const u32));
+                WlanFreeMemory(data_ptr);
+            }
+
+            break;
+        }
+
+        WlanFreeMemory(iface_list_ptr as *const _);
+    }
+
+    WlanCloseHandle(handle, None);
+
+    Some(wifi)
+}
+
+#[cfg(target_os = "windows")]
+fn get_counters(iface: &Interface) -> Option<Counters> {
+    use windows::Win32::NetworkManagement::IpHelper::{GetIfEntry2, MIB_IF_ROW2};
+
+    let mut row = MIB_IF_ROW2 {
+        InterfaceIndex: iface.index,
+        ..Default::default()
+    };
+    if unsafe { GetIfEntry2(&mut row) }.is_err() {
+        return None;
+    }
+
+    Some(Counters {
+        rx_bytes: row.InOctets,
+        tx_bytes: row.OutOctets,
+        rx_packets: row.InUcastPkts + row.InNUcastPkts,
+        tx_packets: row.OutUcastPkts + row.OutNUcastPkts,
+    })
+}
+
+// Kind of adapter from its interface type, and its description for virtual ones
 #[cfg(target_os = "windows")]
-fn get_net() -> NetInfo {
+unsafe fn get_adapter_kind(
+    adapter: &windows::Win32::NetworkManagement::IpHelper::IP_ADAPTER_ADDRESSES_LH,
+) -> &'static str {
+    let description = match adapter.Description.is_null() {
+        true => String::new(),
+        false => adapter.Description.to_string().unwrap_or_default(),
+    };
+
+    // IF_TYPE_* values from ipifcons.h
+    match adapter.IfType {
+        _ if description.contains("WireGuard") => "wireguard",
+        _ if description.contains("TAP-") => "tap",
+        _ if description.contains("Wintun") => "tun",
+        _ if description.contains("Hyper-V Virtual") => "virtual",
+        6 => "ethernet",
+        71 => "wireless",
+        24 => "loopback",
+        23 => "ppp",
+        131 => "tun",
+        _ => "virtual",
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn get_net(config: &NetConfig) -> NetInfo {
     use socket2::SockAddr;
     use std::net::{IpAddr, Ipv4Addr, SocketAddr};
     use windows::Win32::Foundation::{ERROR_BUFFER_OVERFLOW, ERROR_SUCCESS};
     use windows::Win32::NetworkManagement::IpHelper::{
         GetAdaptersAddresses, GetBestInterfaceEx, GAA_FLAG_INCLUDE_GATEWAYS, IF_TYPE_IEEE80211,
-        IP_ADAPTER_ADDRESSES_LH,
-    };
-    use windows::Win32::NetworkManagement::WiFi::{
-        wlan_interface_state_connected, wlan_intf_opcode_current_connection, WlanCloseHandle,
-        WlanEnumInterfaces, WlanOpenHandle, WlanQueryInterface, WLAN_API_VERSION_2_0,
-        WLAN_CONNECTION_ATTRIBUTES, WLAN_INTERFACE_INFO_LIST, WLAN_OPCODE_VALUE_TYPE,
+        IF_TYPE_SOFTWARE_LOOPBACK, IP_ADAPTER_ADDRESSES_LH,
     };
-    use windows::Win32::Networking::WinSock::{AF_INET, SOCKADDR_IN};
-
-    // println!("[DEBUG] Starting network information collection on Windows");
+    use windows::Win32::NetworkManagement::Ndis::IfOperStatusUp;
+    use windows::Win32::Networking::WinSock::AF_UNSPEC;
 
     let mut net_info = NetInfo::default();
 
     unsafe {
-        // Create a socket to determine network route
+        // The adapter used to reach the internet holds the default route
         let google_dns = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(8, 8, 8, 8)), 53);
         let sock_addr = SockAddr::from(google_dns);
         let mut if_index: u32 = 0;
 
-        // println!("[DEBUG] Finding best interface for Google DNS");
         let result = GetBestInterfaceEx(sock_addr.as_ptr() as *const _, &mut if_index);
+        if result != ERROR_SUCCESS.0 {
+            if_index = 0;
+        }
 
-        if result == ERROR_SUCCESS.0 {
-            // println!("[DEBUG] Found best interface index: {}", if_index);
-
-            // Get adapter information
-            let mut buf_len: u32 = 15000;
-            let mut buffer: Vec<u8> = Vec::with_capacity(buf_len as usize);
+        // Get adapter information, IPv4 and IPv6
+        let mut buf_len: u32 = 15000;
+        let mut buffer: Vec<u8> = Vec::with_capacity(buf_len as usize);
 
-            loop {
-                /*
-                println!(
-                    "[DEBUG] Attempting to get adapter addresses with buffer size: {}",
-                    buf_len
-                );
-                */
-                let result = GetAdaptersAddresses(
-                    AF_INET.0.into(),
-                    GAA_FLAG_INCLUDE_GATEWAYS,
-                    None,
-                    Some(buffer.as_mut_ptr() as *mut IP_ADAPTER_ADDRESSES_LH),
-                    &mut buf_len,
-                );
+        loop {
+            let result = GetAdaptersAddresses(
+                AF_UNSPEC.0.into(),
+                GAA_FLAG_INCLUDE_GATEWAYS,
+                None,
+                Some(buffer.as_mut_ptr() as *mut IP_ADAPTER_ADDRESSES_LH),
+                &mut buf_len,
+            );
 
-                if result == ERROR_SUCCESS.0 {
-                    // println!("[DEBUG] Successfully retrieved adapter addresses");
-                    buffer.set_len(buf_len as usize);
-                    break;
-                } else {
-                    // println!("[DEBUG] Need larger buffer for adapter addresses");
-                    buffer = Vec::with_capacity(buf_len as usize);
-                    if result != ERROR_BUFFER_OVERFLOW.0 {
-                        // println!("[DEBUG] Error getting adapter addresses: {}", result);
-                        return net_info;
-                    }
+            if result == ERROR_SUCCESS.0 {
+                buffer.set_len(buf_len as usize);
+                break;
+            } else {
+                buffer = Vec::with_capacity(buf_len as usize);
+                if result != ERROR_BUFFER_OVERFLOW.0 {
+                    return net_info;
                 }
             }
+        }
+
+        // Skip a virtual best adapter (e.g. a VPN) for the first physical one with a gateway
+        if config.exclude_virtual {
+            let mut best = None;
+            let mut fallback = None;
 
-            // Iterate through adapters
             let mut current_addr = buffer.as_ptr() as *const IP_ADAPTER_ADDRESSES_LH;
             while !current_addr.is_null() {
                 let adapter = &*current_addr;
+                let index = adapter.Anonymous1.Anonymous.IfIndex;
+                let physical = !is_virtual(get_adapter_kind(adapter));
 
-                // Check if this is the adapter we wanted
-                let adapter_index = adapter.ZoneIndices[0];
-                if adapter_index == if_index {
-                    // println!("[DEBUG] Found matching adapter index");
-
-                    // Interface name
-                    if !adapter.FriendlyName.is_null() {
-                        if let Ok(name) = adapter.FriendlyName.to_string() {
-                            // println!("[DEBUG] Found interface name: {}", name);
-                            net_info.iface = name;
-                        }
-                    }
+                if index == if_index {
+                    best = Some(physical);
+                }
+                if physical
+                    && fallback.is_none()
+                    && adapter.OperStatus == IfOperStatusUp
+                    && !adapter.FirstGatewayAddress.is_null()
+                {
+                    fallback = Some(index);
+                }
 
-                    // MAC address
-                    if adapter.PhysicalAddressLength > 0 {
-                        let mac = (0..adapter.PhysicalAddressLength)
-                            .map(|i| format!("{:02x}", adapter.PhysicalAddress[i as usize]))
-                            .collect::<Vec<String>>()
-                            .join(":");
-                        // println!("[DEBUG] Found MAC address: {}", mac);
-                        net_info.mac = mac;
-                    }
+                current_addr = adapter.Next;
+            }
+
+            if best != Some(true) {
+                if_index = fallback.unwrap_or(0);
+            }
+        }
+
+        // Iterate through adapters
+        let mut current_addr = buffer.as_ptr() as *const IP_ADAPTER_ADDRESSES_LH;
+        while !current_addr.is_null() {
+            let adapter = &*current_addr;
+
+            let mut iface = Interface {
+                index: adapter.Anonymous1.Anonymous.IfIndex,
+                kind: get_adapter_kind(adapter),
+                up: adapter.OperStatus == IfOperStatusUp,
+                loopback: adapter.IfType == IF_TYPE_SOFTWARE_LOOPBACK,
+                mtu: Some(adapter.Mtu),
+                ..Default::default()
+            };
+
+            // Unknown speeds are reported as u64::MAX
+            if adapter.TransmitLinkSpeed != u64::MAX && adapter.TransmitLinkSpeed > 0 {
+                iface.speed = Some(adapter.TransmitLinkSpeed / 1_000_000);
+            }
+
+            // IF_OPER_STATUS follows the same RFC 2863 states as Linux operstate
+            iface.operstate = Some(
+                match adapter.OperStatus.0 {
+                    1 => "up",
+                    2 => "down",
+                    3 => "testing",
+                    5 => "dormant",
+                    6 => "notpresent",
+                    7 => "lowerlayerdown",
+                    _ => "unknown",
+                }
+                .to_string(),
+            );
+
+            if !adapter.Description.is_null() {
+                iface.driver = adapter.Description.to_string().ok();
+            }
+            iface.counters = get_counters(&iface);
 
-                    // IP address
-                    let mut unicast_addr = adapter.FirstUnicastAddress;
-                    while !unicast_addr.is_null() {
-                        let addr = &*unicast_addr;
-                        let socket_addr = addr.Address.lpSockaddr;
-
-                        if !socket_addr.is_null() {
-                            if (*socket_addr).sa_family == AF_INET {
-                                let ipv4_addr = &*(socket_addr as *const SOCKADDR_IN);
-                                let ip =
-                                    Ipv4Addr::from(ipv4_addr.sin_addr.S_un.S_addr.to_ne_bytes());
-                                // println!("[DEBUG] Found IP address: {}", ip);
-                                net_info.ip = ip.to_string();
-                                break;
-                            }
-                        }
-
-                        unicast_addr = addr.Next;
+            // Interface name
+            if !adapter.FriendlyName.is_null() {
+                if let Ok(name) = adapter.FriendlyName.to_string() {
+                    iface.name = name;
+                }
+            }
+
+            // MAC address
+            if adapter.PhysicalAddressLength > 0 {
+                let mac = (0..adapter.PhysicalAddressLength)
+                    .map(|i| format!("{:02x}", adapter.PhysicalAddress[i as usize]))
+                    .collect::<Vec<String>>()
+                    .join(":");
+                iface.mac = Some(mac);
+            }
+
+            // IP addresses with their prefix length
+            let mut unicast_addr = adapter.FirstUnicastAddress;
+            while !unicast_addr.is_null() {
+                let addr = &*unicast_addr;
+
+                match get_socket_ip(&addr.Address) {
+                    Some(ip @ IpAddr::V4(_)) => iface
+                        .ipv4
+                        .push(format!("{}/{}", ip, addr.OnLinkPrefixLength)),
+                    Some(ip @ IpAddr::V6(_)) => iface
+                        .ipv6
+                        .push(format!("{}/{}", ip, addr.OnLinkPrefixLength)),
+                    None => {}
+                }
+
+                unicast_addr = addr.Next;
+            }
+
+            // Gateways and DNS servers of the adapter we wanted
+            if adapter.Anonymous1.Anonymous.IfIndex == if_index {
+                net_info.iface = iface.name.clone();
+
+                let mut gateway_addr = adapter.FirstGatewayAddress;
+                while !gateway_addr.is_null() {
+                    let addr = &*gateway_addr;
+
+                    match get_socket_ip(&addr.Address) {
+                        Some(ip @ IpAddr::V4(_)) => net_info.gateway = ip.to_string(),
+                        Some(ip @ IpAddr::V6(_)) => net_info.gateway6 = ip.to_string(),
+                        None => {}
                     }
 
-                    // If it's WiFi, try to get SSID and signal strength
-                    if adapter.IfType == IF_TYPE_IEEE80211 {
-                        // println!("[DEBUG] WiFi adapter detected, attempting to get WiFi info");
-
-                        // Try to get WiFi information
-                        let mut handle = Default::default();
-                        let client_version = WLAN_API_VERSION_2_0;
-                        let mut negotiated_version = 0;
-
-                        let result = WlanOpenHandle(
-                            client_version,
-                            None,
-                            &mut negotiated_version,
-                            &mut handle,
-                        );
-
-                        if result == ERROR_SUCCESS.0 {
-                            // println!("[DEBUG] Successfully opened WLAN handle");
-
-                            let mut iface_list_ptr = std::ptr::null_mut();
-                            let result = WlanEnumInterfaces(handle, None, &mut iface_list_ptr);
-
-                            if result == ERROR_SUCCESS.0 && !iface_list_ptr.is_null() {
-                                // println!("[DEBUG] Successfully enumerated WLAN interfaces");
-
-                                let iface_list =
-                                    &*(iface_list_ptr as *const WLAN_INTERFACE_INFO_LIST);
-
-                                for i in 0..iface_list.dwNumberOfItems {
-                                    let iface_info = &iface_list.InterfaceInfo[i as usize];
-
-                                    // If we find SSID and signal information, save it
-                                    if iface_info.isState == wlan_interface_state_connected {
-                                        // println!("[DEBUG] Found connected WiFi interface");
-
-                                        let mut data_size = 0u32;
-                                        let mut data_ptr = std::ptr::null_mut();
-                                        let mut opcode_value_type = WLAN_OPCODE_VALUE_TYPE(0);
-
-                                        let result = WlanQueryInterface(
-                                            handle,
-                                            &iface_info.InterfaceGuid,
-                                            wlan_intf_opcode_current_connection,
-                                            None,
-                                            &mut data_size,
-                                            &mut data_ptr,
-                                            Some(&mut opcode_value_type),
-                                        );
-
-                                        if result == ERROR_SUCCESS.0 && !data_ptr.is_null() {
-                                            let conn_attr =
-                                                *(data_ptr as *const WLAN_CONNECTION_ATTRIBUTES);
-                                            let ssid_bytes =
-                                                &conn_attr.wlanAssociationAttributes.dot11Ssid;
-                                            let ssid = String::from_utf8_lossy(
-                                                &ssid_bytes.ucSSID
-                                                    [..ssid_bytes.uSSIDLength as usize],
-                                            );
-                                            net_info.wifi_ssid = ssid.to_string();
-                                            net_info.wifi_signal = format!(
-                                                "{} dBm",
-                                                conn_attr
-                                                    .wlanAssociationAttributes
-                                                    .wlanSignalQuality
-                                            );
-                                            // wlanSignalQuality está en 0-100, puedes convertirlo a dBm si querés
-                                        }
-                                    }
-                                }
-                            } else {
-                                // println!("[DEBUG] Failed to enumerate WLAN interfaces");
-                            }
-                        } else {
-                            // println!("[DEBUG] Failed to open WLAN handle");
-                        }
-
-                        WlanCloseHandle(handle, None);
+                    gateway_addr = addr.Next;
+                }
+
+                let mut dns_addr = adapter.FirstDnsServerAddress;
+                while !dns_addr.is_null() {
+                    let addr = &*dns_addr;
+
+                    if let Some(ip) = get_socket_ip(&addr.Address) {
+                        net_info.dns.push(ip.to_string());
                     }
 
-                    break;
+                    dns_addr = addr.Next;
                 }
 
-                current_addr = adapter.Next;
+                // If it's WiFi, try to get SSID and signal strength
+                if adapter.IfType == IF_TYPE_IEEE80211 {
+                    net_info.wifi = get_wifi(&net_info.iface);
+                }
             }
-        } else {
-            // println!("[DEBUG] Failed to find best interface, error: {}", result);
+
+            net_info.interfaces.push(iface);
+            current_addr = adapter.Next;
         }
     }
 
     net_info
 }
 
-pub fn load_net_info(info: &mut SystemInfo) {
-    // println!("[DEBUG] Loading network information into SystemInfo");
-    let net = get_net();
+// Read the counters again after the interval, for bytes per second
+fn sample_throughput(interfaces: &mut [Interface], interval: Duration) {
+    std::thread::sleep(interval);
+
+    for iface in interfaces.iter_mut() {
+        if let (Some(before), Some(after)) = (iface.counters, get_counters(iface)) {
+            let seconds = interval.as_secs_f64();
+            iface.throughput = Some((
+                after.rx_bytes.saturating_sub(before.rx_bytes) as f64 / seconds,
+                after.tx_bytes.saturating_sub(before.tx_bytes) as f64 / seconds,
+            ));
+            iface.counters = Some(after);
+        }
+    }
+}
+
+fn format_bytes(bytes: f64) -> String {
+    let size = get_size(bytes);
+    format!("{:.2} {}", size.metric_value, size.metric)
+}
+
+// Link speed in Mbit/s, e.g. "100 Mb/s", "2.5 Gb/s" or "10 Gb/s"
+fn format_speed(speed: u64) -> String {
+    if speed >= 1000 {
+        format!("{} Gb/s", speed as f64 / 1000.0)
+    } else {
+        format!("{} Mb/s", speed)
+    }
+}
+
+// Traffic and link fields of an interface, e.g. net_rx or net_0_rx
+fn insert_link_info(prefix: &str, iface: &Interface, throughput: bool, info: &mut SystemInfo) {
+    let unknown = || "Unknown".to_string();
+    let counters = iface.counters;
+
+    let mut insert = |field: &str, value: Option<String>| {
+        info.insert(
+            format!("{}_{}", prefix, field),
+            value.unwrap_or_else(unknown),
+        );
+    };
+
+    insert("rx", counters.map(|c| format_bytes(c.rx_bytes as f64)));
+    insert("tx", counters.map(|c| format_bytes(c.tx_bytes as f64)));
+    insert("rx_packets", counters.map(|c| c.rx_packets.to_string()));
+    insert("tx_packets", counters.map(|c| c.tx_packets.to_string()));
+    insert("speed", iface.speed.map(format_speed));
+    insert("duplex", iface.duplex.clone());
+    insert("mtu", iface.mtu.map(|mtu| mtu.to_string()));
+    insert("operstate", iface.operstate.clone());
+    insert("driver", iface.driver.clone());
+
+    // Throughput is only sampled when enabled in the config
+    let rate = |bytes: f64| format!("{}/s", format_bytes(bytes));
+    let (rx_rate, tx_rate) = match (iface.throughput, throughput) {
+        (Some((rx, tx)), _) => (Some(rate(rx)), Some(rate(tx))),
+        (None, true) => (None, None),
+        (None, false) => (Some("N/A".to_string()), Some("N/A".to_string())),
+    };
+    insert("rx_rate", rx_rate);
+    insert("tx_rate", tx_rate);
+}
+
+// First address without its prefix length, e.g. "192.168.1.10/24" -> "192.168.1.10"
+fn strip_prefix_length(addresses: &[String]) -> String {
+    addresses
+        .first()
+        .and_then(|address| address.split('/').next())
+        .unwrap_or("None")
+        .to_string()
+}
+
+pub fn load_net_info(config: &NetConfig, info: &mut SystemInfo) {
+    let mut net = get_net(config);
+
+    let throughput = config.throughput_interval > 0;
+    if throughput {
+        sample_throughput(
+            &mut net.interfaces,
+            Duration::from_millis(config.throughput_interval),
+        );
+    }
+    let join = |addresses: &[String]| match addresses.is_empty() {
+        true => "None".to_string(),
+        false => addresses.join(", "),
+    };
+
+    // The primary interface holds the default route
+    let primary = net.interfaces.iter().find(|iface| iface.name == net.iface);
+    let (ipv4, ipv6) = primary
+        .map(|iface| (iface.ipv4.as_slice(), iface.ipv6.as_slice()))
+        .unwrap_or_default();
+
+    info.insert("net_iface".to_string(), net.iface.clone());
+    info.insert(
+        "net_type".to_string(),
+        primary.map_or("Unknown", |iface| iface.kind).to_string(),
+    );
+    info.insert("net_ip".to_string(), strip_prefix_length(ipv4));
+    info.insert("net_ip6".to_string(), strip_prefix_length(ipv6));
+    info.insert("net_ipv4".to_string(), join(ipv4));
+    info.insert("net_ipv6".to_string(), join(ipv6));
+    info.insert(
+        "net_mac".to_string(),
+        primary
+            .and_then(|iface| iface.mac.clone())
+            .unwrap_or_else(|| "None".to_string()),
+    );
+    insert_link_info(
+        "net",
+        primary.unwrap_or(&Interface::default()),
+        throughput,
+        info,
+    );
+    info.insert("net_gateway".to_string(), net.gateway);
+    info.insert("net_gateway6".to_string(), net.gateway6);
+    info.insert("net_dns".to_string(), join(&net.dns));
+
+    // Not connected, every Wi-Fi field is None
+    let wifi = net.wifi.filter(|wifi| wifi.ssid.is_some());
+    let connected = wifi.is_some();
+    let wifi = wifi.unwrap_or_default();
+    let field = |value: Option<String>| match (value, connected) {
+        (Some(value), _) => value,
+        (None, true) => "Unknown".to_string(),
+        (None, false) => "None".to_string(),
+    };
+
+    info.insert(
+        "net_wifi_iface".to_string(),
+        field(connected.then_some(wifi.iface)),
+    );
+    info.insert("net_wifi_ssid".to_string(), field(wifi.ssid));
+    info.insert("net_wifi_bssid".to_string(), field(wifi.bssid));
+    info.insert(
+        "net_wifi_signal".to_string(),
+        field(wifi.signal.map(|signal| format!("{} dBm", signal))),
+    );
+    info.insert(
+        "net_wifi_quality".to_string(),
+        field(wifi.quality.map(|quality| format!("{}%", quality))),
+    );
+    info.insert(
+        "net_wifi_frequency".to_string(),
+        field(wifi.frequency.map(|frequency| format!("{} MHz", frequency))),
+    );
+    info.insert(
+        "net_wifi_band".to_string(),
+        field(
+            wifi.frequency
+                .map(|frequency| get_band(frequency).to_string()),
+        ),
+    );
+    info.insert(
+        "net_wifi_channel".to_string(),
+        field(wifi.channel.map(|channel| channel.to_string())),
+    );
+    info.insert(
+        "net_wifi_width".to_string(),
+        field(wifi.width.map(|width| format!("{} MHz", width))),
+    );
+    info.insert(
+        "net_wifi_bitrate".to_string(),
+        field(
+            wifi.bitrate
+                .map(|bitrate| format!("{:.1} Mbit/s", bitrate as f64 / 1000.0)),
+        ),
+    );
+    info.insert("net_wifi_security".to_string(), field(wifi.security));
+
+    info.insert("net_count".to_string(), net.interfaces.len().to_string());
+
+    for (i, iface) in net.interfaces.iter().enumerate() {
+        let prefix = format!("net_{}", i);
+        let state = if iface.up { "up" } else { "down" };
+
+        info.insert(format!("{}_name", prefix), iface.name.clone());
+        info.insert(format!("{}_type", prefix), iface.kind.to_string());
+        info.insert(
+            format!("{}_mac", prefix),
+            iface.mac.clone().unwrap_or_else(|| "None".to_string()),
+        );
+        info.insert(format!("{}_state", prefix), state.to_string());
+        info.insert(format!("{}_ipv4", prefix), join(&iface.ipv4));
+        info.insert(format!("{}_ipv6", prefix), join(&iface.ipv6));
+        insert_link_info(&prefix, iface, throughput, info);
+    }
 
-    info.insert("net_ip".to_string(), net.ip);
-    info.insert("net_mac".to_string(), net.mac);
-    info.insert("net_iface".to_string(), net.iface);
-    info.insert("net_wifi_ssid".to_string(), net.wifi_ssid);
-    info.insert("net_wifi_signal".to_string(), net.wifi_signal);
+    // Interfaces with an address, e.g. "eth0 (192.168.1.10/24), docker0 (172.17.0.1/16)"
+    let summary: Vec<String> = net
+        .interfaces
+        .iter()
+        .filter(|iface| iface.up && !iface.loopback)
+        .filter_map(|iface| {
+            let address = iface.ipv4.first().or(iface.ipv6.first())?;
+            Some(format!("{} ({})", iface.name, address))
+        })
+        .collect();
+    info.insert("net_summary".to_string(), join(&summary));
 
-    // println!("[DEBUG] Network information loaded successfully");
+    // Tunnels that are up, e.g. wg0 or tun0
+    let vpn: Vec<String> = net
+        .interfaces
+        .iter()
+        .filter(|iface| iface.up && is_vpn(iface.kind))
+        .map(|iface| iface.name.clone())
+        .collect();
+    info.insert(
+        "net_vpn_active".to_string(),
+        if vpn.is_empty() { "No" } else { "Yes" }.to_string(),
+    );
+    info.insert("net_vpn_iface".to_string(), join(&vpn));
 }
@@ -1,11 +1,17 @@
+use std::collections::HashMap;
 use std::env;
 
 use super::SystemInfo;
+use crate::config::OsConfig;
 
 struct OsInfo {
     name: String,
     version: String,
     hostname: String,
+    release: HashMap<String, String>, // os-release fields (ID, VERSION_ID, ...)
+    fqdn: String,                     // Fully qualified domain name (e.g. vm.example.com)
+    pretty_hostname: String,          // Free-form hostname (e.g. Lennart's Laptop)
+    machine_id: String,               // Unique installation ID, /etc/machine-id on Linux
 }
 
 impl Default for OsInfo {
@@ -14,34 +20,247 @@ impl Default for OsInfo {
             name: format!("{} {}", env::consts::OS, env::consts::ARCH),
             version: "Unknown".to_string(),
             hostname: "Unknown".to_string(),
+            release: HashMap::new(),
+            fqdn: "Unknown".to_string(),
+            pretty_hostname: "Unknown".to_string(),
+            machine_id: "Unknown".to_string(),
         }
     }
 }
 
+// Distributions without versioned releases, for os-release files that still set VERSION_ID
+const ROLLING_DISTROS: &[&str] = &[
+    "arch",
+    "artix",
+    "endeavouros",
+    "manjaro",
+    "cachyos",
+    "garuda",
+    "gentoo",
+    "void",
+    "opensuse-tumbleweed",
+    "opensuse-slowroll",
+    "kali",
+    "solus",
+    "chimera",
+];
+
+// Days since 1970-01-01 for a civil date (Howard Hinnant's algorithm)
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+// SUPPORT_END is a date like "2025-05-13"
+fn is_past(date: &str) -> Option<bool> {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let mut parts = date.splitn(3, '-').map(|part| part.parse::<i64>());
+    let (year, month, day) = (
+        parts.next()?.ok()?,
+        parts.next()?.ok()?,
+        parts.next()?.ok()?,
+    );
+
+    let today = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs() as i64 / 86400;
+    Some(days_from_civil(year, month, day) < today)
+}
+
+// Application ID mixed into the machine ID, like sd_id128_get_machine_app_specific()
+const MACHINE_APP_ID: [u8; 16] = [
+    0x2a, 0x90, 0x08, 0xd4, 0x60, 0xc8, 0x49, 0x4b, 0xa8, 0x57, 0xae, 0xed, 0xfc, 0x05, 0x41, 0x45,
+];
+
+// App-specific ID derived like sd_id128_get_machine_app_specific(): HMAC-SHA256 keyed
+// with the machine ID, truncated to a v4 UUID, so it can't be traced back to the
+// ID other programs use. MachineGuid on Windows has dashes, they are dropped
+fn hash_machine_id(machine_id: &str) -> String {
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    let hex: String = machine_id.chars().filter(|&c| c != '-').collect();
+    let key: Vec<u8> = match hex.len() {
+        32 => (0..32)
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+            .collect::<Result<_, _>>()
+            .unwrap_or_else(|_| machine_id.as_bytes().to_vec()),
+        _ => machine_id.as_bytes().to_vec(),
+    };
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(&key).expect("HMAC takes keys of any size");
+    mac.update(&MACHINE_APP_ID);
+    let mut id = mac.finalize().into_bytes();
+    id[6] = (id[6] & 0x0f) | 0x40;
+    id[8] = (id[8] & 0x3f) | 0x80;
+    id[..16]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+// Current hostname from the kernel, which /etc/hostname only sets at boot
 #[cfg(target_os = "linux")]
-fn get_os() -> OsInfo {
+fn get_hostname() -> Option<String> {
     use std::fs;
 
-    let mut os = OsInfo::default();
+    let mut buffer = [0u8; 256];
+    if unsafe { libc::gethostname(buffer.as_mut_ptr() as *mut libc::c_char, buffer.len()) } == 0 {
+        let length = buffer.iter().position(|&b| b == 0).unwrap_or(buffer.len());
+        let hostname = String::from_utf8_lossy(&buffer[..length]).to_string();
+        if !hostname.is_empty() {
+            return Some(hostname);
+        }
+    }
 
-    if let Ok(os_release) = fs::read_to_string("/etc/os-release") {
-        for line in os_release.lines() {
-            if line.starts_with("PRETTY_NAME=") {
-                let value = line
-                    .trim_start_matches("PRETTY_NAME=")
-                    .trim_matches('"')
-                    .to_string();
-                os.name = value;
-                break;
-            }
+    ["/proc/sys/kernel/hostname", "/etc/hostname"]
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .map(|hostname| hostname.trim().to_string())
+        .find(|hostname| !hostname.is_empty())
+}
+
+// Maximum time to wait for NSS to resolve the hostname, with DNS in nsswitch.conf
+// getaddrinfo() can block on the network
+#[cfg(target_os = "linux")]
+const RESOLVE_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(300);
+
+// Canonical name of the host through NSS, like "hostname --fqdn", off the main thread
+// so a slow or unreachable DNS server can't hold up the output
+#[cfg(target_os = "linux")]
+fn get_canonical_name(hostname: &str) -> Option<String> {
+    use std::ffi::{CStr, CString};
+    use std::sync::mpsc;
+    use std::thread;
+
+    let name = CString::new(hostname).ok()?;
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        let hints = libc::addrinfo {
+            ai_flags: libc::AI_CANONNAME,
+            ai_family: libc::AF_UNSPEC,
+            ..unsafe { std::mem::zeroed() }
+        };
+        let mut result: *mut libc::addrinfo = std::ptr::null_mut();
+        if unsafe { libc::getaddrinfo(name.as_ptr(), std::ptr::null(), &hints, &mut result) } != 0 {
+            return;
+        }
+
+        let canonical = unsafe { (*result).ai_canonname };
+        let fqdn = (!canonical.is_null()).then(|| {
+            unsafe { CStr::from_ptr(canonical) }
+                .to_string_lossy()
+                .to_string()
+        });
+        unsafe { libc::freeaddrinfo(result) };
+
+        if let Some(fqdn) = fqdn {
+            let _ = sender.send(fqdn);
+        }
+    });
+
+    receiver.recv_timeout(RESOLVE_TIMEOUT).ok()
+}
+
+// Resolve the FQDN from local sources first: "127.0.1.1 vm.example.com vm" in
+// /etc/hosts, then the "domain" or "search" line in /etc/resolv.conf, and only then
+// NSS, which may ask DNS
+#[cfg(target_os = "linux")]
+fn get_fqdn(hostname: &str) -> Option<String> {
+    use std::fs;
+
+    if hostname.contains('.') {
+        return Some(hostname.to_string());
+    }
+
+    if let Ok(hosts) = fs::read_to_string("/etc/hosts") {
+        let prefix = format!("{}.", hostname);
+        let fqdn = hosts
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default())
+            .filter_map(|line| {
+                let names: Vec<&str> = line.split_whitespace().skip(1).collect();
+                names.contains(&hostname).then_some(names)
+            })
+            .flatten()
+            .find(|name| name.starts_with(&prefix));
+        if let Some(fqdn) = fqdn {
+            return Some(fqdn.to_string());
         }
     }
 
-    if let Ok(hostname) = fs::read_to_string("/etc/hostname") {
-        os.hostname = hostname.trim().to_string();
+    // The last of "domain" and "search" wins, the first search domain is the local one
+    let domain = fs::read_to_string("/etc/resolv.conf")
+        .ok()
+        .and_then(|resolv| {
+            resolv.lines().rev().find_map(|line| {
+                let mut words = line.split_whitespace();
+                match words.next() {
+                    Some("domain" | "search") => words.next().map(str::to_string),
+                    _ => None,
+                }
+            })
+        })
+        .filter(|domain| domain != ".");
+    if let Some(domain) = domain {
+        return Some(format!("{}.{}", hostname, domain));
     }
 
-    return os;
+    get_canonical_name(hostname).filter(|fqdn| fqdn.contains('.'))
+}
+
+#[cfg(target_os = "linux")]
+fn get_os() -> OsInfo {
+    use crate::utils::{parse_key_values, read_os_release};
+    use std::fs;
+
+    let mut os = OsInfo {
+        release: read_os_release(),
+        ..Default::default()
+    };
+
+    // NAME and VERSION are the fallbacks for distributions without PRETTY_NAME
+    if let Some(name) = os.release.get("PRETTY_NAME") {
+        os.name = name.clone();
+    } else if let Some(name) = os.release.get("NAME") {
+        os.name = match os.release.get("VERSION") {
+            Some(version) => format!("{} {}", name, version),
+            None => name.clone(),
+        };
+    }
+
+    if let Some(version) = os.release.get("VERSION_ID").or(os.release.get("VERSION")) {
+        os.version = version.clone();
+    }
+
+    if let Some(hostname) = get_hostname() {
+        os.fqdn = get_fqdn(&hostname).unwrap_or_else(|| hostname.clone());
+        os.hostname = hostname;
+    }
+
+    // PRETTY_HOSTNAME="Lennart's Laptop", set by hostnamectl
+    os.pretty_hostname = fs::read_to_string("/etc/machine-info")
+        .ok()
+        .and_then(|content| parse_key_values(&content).remove("PRETTY_HOSTNAME"))
+        .filter(|hostname| !hostname.is_empty())
+        .unwrap_or_else(|| "None".to_string());
+
+    // dbus keeps its own copy on systems without systemd
+    if let Some(machine_id) = ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .map(|machine_id| machine_id.trim().to_string())
+        .find(|machine_id| !machine_id.is_empty() && machine_id != "uninitialized")
+    {
+        os.machine_id = machine_id;
+    }
+
+    os
 }
 
 #[cfg(target_os = "windows")]
@@ -50,7 +269,8 @@ fn get_os() -> OsInfo {
     use windows::{
         core::PWSTR,
         Win32::System::SystemInformation::{
-            ComputerNameDnsHostname, GetComputerNameExW, GetVersionExW, OSVERSIONINFOEXW,
+            ComputerNameDnsFullyQualified, ComputerNameDnsHostname, GetComputerNameExW,
+            GetVersionExW, OSVERSIONINFOEXW,
         },
     };
     use winreg::{enums::HKEY_LOCAL_MACHINE, RegKey};
@@ -74,11 +294,25 @@ fn get_os() -> OsInfo {
         }
     }
 
-    let computer_name = OsString::from_wide(&computer_name)
+    let computer_name = OsString::from_wide(&computer_name[..size as usize])
         .to_string_lossy()
         .to_string();
     os.hostname = computer_name;
 
+    let mut fqdn: Vec<u16> = vec![0; 256];
+    let mut size = fqdn.len() as u32;
+    os.fqdn = match unsafe {
+        GetComputerNameExW(
+            ComputerNameDnsFullyQualified,
+            Some(PWSTR(fqdn.as_mut_ptr())),
+            &mut size,
+        )
+    } {
+        Ok(()) => String::from_utf16_lossy(&fqdn[..size as usize]),
+        Err(_) => os.hostname.clone(),
+    };
+    os.pretty_hostname = "None".to_string();
+
     // Get OS version and name
     let mut version_info = OSVERSIONINFOEXW {
         dwOSVersionInfoSize: std::mem::size_of::<OSVERSIONINFOEXW>() as u32,
@@ -110,15 +344,120 @@ fn get_os() -> OsInfo {
         }
     }
 
+    // The Windows counterpart of /etc/machine-id
+    if let Ok(machine_id) = hkey
+        .open_subkey(r"SOFTWARE\Microsoft\Cryptography")
+        .and_then(|key| key.get_value::<String, _>("MachineGuid"))
+    {
+        os.machine_id = machine_id;
+    }
+    os.release.insert("ID".to_string(), "windows".to_string());
+    os.release.insert("VERSION_ID".to_string(), version.clone());
+    os.release.insert(
+        "BUILD_ID".to_string(),
+        version_info.dwBuildNumber.to_string(),
+    );
     os.version = version;
 
     os
 }
 
-pub fn load_os_info(info: &mut SystemInfo) {
+pub fn load_os_info(config: &OsConfig, info: &mut SystemInfo) {
     let os = get_os();
+    let field = |key: &str| os.release.get(key).cloned().unwrap_or_default();
+
+    for (key, var) in [
+        ("ID", "os_id"),
+        ("ID_LIKE", "os_id_like"),
+        ("VERSION_ID", "os_version_id"),
+        ("BUILD_ID", "os_build_id"),
+        ("VARIANT", "os_variant"),
+        ("HOME_URL", "os_home_url"),
+        ("SUPPORT_END", "os_support_end"),
+        ("ANSI_COLOR", "os_ansi_color"),
+    ] {
+        info.insert(var.to_string(), field(key));
+    }
+
+    // Ubuntu keeps the codename in UBUNTU_CODENAME, older releases only in VERSION
+    let codename = [field("VERSION_CODENAME"), field("UBUNTU_CODENAME")]
+        .into_iter()
+        .find(|codename| !codename.is_empty())
+        .or_else(|| {
+            let version = field("VERSION");
+            let (_, codename) = version.split_once('(')?;
+            Some(codename.trim_end_matches(')').to_string())
+        })
+        .unwrap_or_default();
+    info.insert("os_codename".to_string(), codename);
+
+    // The accent color as an escape sequence, e.g. "0;38;2;23;147;209" for Arch
+    let color = match field("ANSI_COLOR") {
+        color if color.is_empty() => String::new(),
+        color => format!("\x1b[{}m", color),
+    };
+    info.insert("os_color".to_string(), color);
+
+    let id = field("ID");
+    let rolling = ROLLING_DISTROS.contains(&id.as_str())
+        || field("BUILD_ID") == "rolling"
+        || (field("VERSION_ID").is_empty() && !id.is_empty() && id != "windows");
+    info.insert(
+        "os_release_type".to_string(),
+        if rolling { "rolling" } else { "point" }.to_string(),
+    );
+
+    let support_end = field("SUPPORT_END");
+    let warning = match is_past(&support_end) {
+        Some(true) => format!("(support ended on {})", support_end),
+        _ => String::new(),
+    };
+    info.insert("os_support_warning".to_string(), warning);
 
     info.insert("os_name".to_string(), os.name);
     info.insert("os_version".to_string(), os.version);
+    // "vm.example.com" -> "vm" and "example.com"
+    let (short, domain) = match os.fqdn.split_once('.') {
+        Some((short, domain)) => (short.to_string(), domain.to_string()),
+        None => (os.hostname.clone(), "None".to_string()),
+    };
+    info.insert("os_hostname_short".to_string(), short);
+    info.insert("os_domain".to_string(), domain);
+    info.insert("os_fqdn".to_string(), os.fqdn);
+    info.insert("os_pretty_hostname".to_string(), os.pretty_hostname);
+
+    let machine_id = match config.machine_id.as_str() {
+        _ if os.machine_id == "Unknown" => os.machine_id,
+        "full" => os.machine_id,
+        "hidden" => "Hidden".to_string(),
+        _ => hash_machine_id(&os.machine_id),
+    };
+    info.insert("os_machine_id".to_string(), machine_id);
+
     info.insert("os_hostname".to_string(), os.hostname);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Pinned so the derived ID stays stable across releases, checked against Python's
+    // hmac.new(machine_id, MACHINE_APP_ID, sha256) with the v4 UUID bits set
+    #[test]
+    fn app_specific_machine_id() {
+        let id = "233c831df93a45e8b9093acc1be6893a";
+        assert_eq!(hash_machine_id("0123456789abcdef0123456789abcdef"), id);
+        assert_eq!(hash_machine_id("01234567-89ab-cdef-0123-456789abcdef"), id);
+        assert_eq!(hash_machine_id("0123456789ABCDEF0123456789ABCDEF"), id);
+    }
+
+    #[test]
+    fn machine_id_is_a_v4_uuid() {
+        for machine_id in ["", "not-hex", "ffffffffffffffffffffffffffffffff"] {
+            let id = hash_machine_id(machine_id);
+            assert_eq!(id.len(), 32);
+            assert_eq!(&id[12..13], "4");
+            assert!(["8", "9", "a", "b"].contains(&&id[16..17]));
+        }
+    }
+}
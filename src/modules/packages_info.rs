@@ -1,12 +1,14 @@
 use std::collections::HashMap;
 
 use super::SystemInfo;
+use crate::config::PackagesConfig;
 
 struct PackagesInfo {
     count: usize,
     total_count: usize,
     manager: String,
     packages: HashMap<String, usize>,
+    scopes: HashMap<String, usize>, // e.g. flatpak_user, nix_system
 }
 
 impl Default for PackagesInfo {
@@ -16,48 +18,787 @@ impl Default for PackagesInfo {
             total_count: 0,
             manager: "Unknown".to_string(),
             packages: HashMap::new(),
+            scopes: HashMap::new(),
         }
     }
 }
 
+// Package manager that owns the distribution, from os-release ID and ID_LIKE
 #[cfg(target_os = "linux")]
-fn get_packages() -> PackagesInfo {
-    use std::process::Command;
+const DISTRO_MANAGERS: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "debian" => "dpkg",
+    "ubuntu" => "dpkg",
+    "arch" => "pacman",
+    "fedora" => "rpm",
+    "rhel" => "rpm",
+    "centos" => "rpm",
+    "suse" => "rpm",
+    "opensuse" => "rpm",
+    "mageia" => "rpm",
+    "alpine" => "apk",
+    "void" => "xbps",
+    "gentoo" => "portage",
+};
 
-    let mut info = PackagesInfo::default();
+// Counts the installed packages of a manager, None when it is not installed
+#[cfg(target_os = "linux")]
+type PackageCounter = fn() -> Option<usize>;
 
-    // Count packages on different package managers
-    let package_managers = [
-        ("dpkg", "dpkg-query -f '${binary:Package}\n' -W | wc -l"),
-        ("rpm", "rpm -qa | wc -l"),
-        ("pacman", "pacman -Q | wc -l"),
-    ];
+#[cfg(target_os = "linux")]
+const PACKAGE_MANAGERS: &[(&str, PackageCounter)] = &[
+    ("dpkg", count_dpkg),
+    ("rpm", count_rpm),
+    ("pacman", count_pacman),
+    ("apk", count_apk),
+    ("xbps", count_xbps),
+    ("portage", count_portage),
+];
+
+// Count entries of a directory, optionally only subdirectories
+#[cfg(target_os = "linux")]
+fn count_dir(path: &str, only_dirs: bool) -> Option<usize> {
+    use std::fs;
+
+    let entries = fs::read_dir(path).ok()?;
+    Some(
+        entries
+            .flatten()
+            .filter(|entry| !only_dirs || entry.path().is_dir())
+            .count(),
+    )
+}
+
+// dpkg: one paragraph per package in /var/lib/dpkg/status
+#[cfg(target_os = "linux")]
+fn count_dpkg() -> Option<usize> {
+    let status = std::fs::read_to_string("/var/lib/dpkg/status").ok()?;
+    Some(
+        status
+            .lines()
+            .filter(|line| line.starts_with("Status: ") && line.ends_with(" installed"))
+            .count(),
+    )
+}
+
+// pacman: one directory per package in /var/lib/pacman/local
+#[cfg(target_os = "linux")]
+fn count_pacman() -> Option<usize> {
+    count_dir("/var/lib/pacman/local", true)
+}
 
-    let mut is_primary = true;
+// apk: one "P:" line per package in /lib/apk/db/installed
+#[cfg(target_os = "linux")]
+fn count_apk() -> Option<usize> {
+    let installed = std::fs::read_to_string("/lib/apk/db/installed").ok()?;
+    Some(
+        installed
+            .lines()
+            .filter(|line| line.starts_with("P:"))
+            .count(),
+    )
+}
 
-    for (manager, cmd) in &package_managers {
-        if let Ok(output) = Command::new("sh").arg("-c").arg(cmd).output() {
-            if output.status.success() {
-                let count_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                let count = count_str.parse::<usize>().unwrap();
+// xbps: one "pkgver" key per package in /var/db/xbps/pkgdb-<version>.plist
+#[cfg(target_os = "linux")]
+fn count_xbps() -> Option<usize> {
+    use std::fs;
+
+    let pkgdb = fs::read_dir("/var/db/xbps").ok()?.flatten().find(|entry| {
+        let name = entry.file_name().to_string_lossy().to_string();
+        name.starts_with("pkgdb-") && name.ends_with(".plist")
+    })?;
 
-                info.packages.insert(manager.to_string(), count);
-                info.total_count += count;
+    let plist = fs::read_to_string(pkgdb.path()).ok()?;
+    Some(plist.matches("<key>pkgver</key>").count())
+}
+
+// Portage: /var/db/pkg/<category>/<package>
+#[cfg(target_os = "linux")]
+fn count_portage() -> Option<usize> {
+    use std::fs;
+
+    let categories = fs::read_dir("/var/db/pkg").ok()?;
+    Some(
+        categories
+            .flatten()
+            .filter_map(|category| count_dir(&category.path().to_string_lossy(), true))
+            .sum(),
+    )
+}
+
+// rpm: rows of the Packages table in the sqlite database (rpm >= 4.16),
+// older BerkeleyDB/ndb databases need the rpm tool
+#[cfg(target_os = "linux")]
+fn count_rpm() -> Option<usize> {
+    use crate::utils::run_output_with_timeout;
+    use std::time::Duration;
 
-                if is_primary {
-                    info.count = count;
-                    info.manager = manager.to_string();
-                    is_primary = false;
+    if let Some(count) = sqlite::count_rows("/var/lib/rpm/rpmdb.sqlite", "Packages")
+        .or_else(|| sqlite::count_rows("/usr/lib/sysimage/rpm/rpmdb.sqlite", "Packages"))
+    {
+        return Some(count);
+    }
+
+    run_output_with_timeout("rpm", &["-qa"], Duration::from_secs(2))
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).lines().count())
+}
+
+// Minimal read-only SQLite reader, just enough to count the rows of a table
+#[cfg(target_os = "linux")]
+mod sqlite {
+    use std::fs;
+
+    struct Database {
+        data: Vec<u8>,
+        page_size: usize,
+    }
+
+    // Big-endian variable length integer, returns (value, length)
+    fn read_varint(data: &[u8]) -> Option<(u64, usize)> {
+        let mut value: u64 = 0;
+        for i in 0..9 {
+            let byte = *data.get(i)?;
+            if i == 8 {
+                return Some(((value << 8) | byte as u64, 9));
+            }
+            value = (value << 7) | (byte & 0x7f) as u64;
+            if byte & 0x80 == 0 {
+                return Some((value, i + 1));
+            }
+        }
+        None
+    }
+
+    impl Database {
+        fn page(&self, number: usize) -> Option<&[u8]> {
+            let start = number.checked_sub(1)?.checked_mul(self.page_size)?;
+            self.data.get(start..start.checked_add(self.page_size)?)
+        }
+
+        // Page size minus the reserved space at the end of every page
+        fn usable_size(&self) -> usize {
+            self.page_size - *self.data.get(20).unwrap_or(&0) as usize
+        }
+
+        // Visit every cell of the table b-tree rooted at `root`
+        fn walk(&self, root: usize, depth: usize, visit: &mut dyn FnMut(&[u8])) -> Option<()> {
+            // Guard against corrupted (cyclic) trees
+            if depth > 64 {
+                return None;
+            }
+
+            let page = self.page(root)?;
+            // The first page starts with the 100-byte database header
+            let header = if root == 1 { 100 } else { 0 };
+            let kind = *page.get(header)?;
+            let cells = u16::from_be_bytes([*page.get(header + 3)?, *page.get(header + 4)?]);
+            let pointers = header + if kind == 0x05 { 12 } else { 8 };
+
+            for i in 0..cells as usize {
+                let at = pointers + i * 2;
+                let offset = u16::from_be_bytes([*page.get(at)?, *page.get(at + 1)?]) as usize;
+                let cell = page.get(offset..)?;
+
+                match kind {
+                    // Interior table page: 4-byte left child, then the rowid
+                    0x05 => {
+                        let child = u32::from_be_bytes(cell.get(..4)?.try_into().ok()?);
+                        self.walk(child as usize, depth + 1, visit)?;
+                    }
+                    // Leaf table page: payload size, rowid, payload
+                    0x0d => visit(cell),
+                    _ => return None,
                 }
             }
+
+            if kind == 0x05 {
+                let at = header + 8;
+                let right = u32::from_be_bytes(page.get(at..at + 4)?.try_into().ok()?);
+                self.walk(right as usize, depth + 1, visit)?;
+            }
+
+            Some(())
         }
+
+        // Payload of a leaf cell, following its overflow pages when it doesn't fit
+        fn payload(&self, cell: &[u8]) -> Option<Vec<u8>> {
+            let (size, n) = read_varint(cell)?;
+            let (_, m) = read_varint(cell.get(n..)?)?;
+            let cell = cell.get(n + m..)?;
+            let size = usize::try_from(size)
+                .ok()
+                .filter(|&size| size <= self.data.len())?;
+
+            let usable = self.usable_size();
+            let max_local = usable.checked_sub(35)?;
+            if size <= max_local {
+                return cell.get(..size).map(<[u8]>::to_vec);
+            }
+
+            let min_local = ((usable - 12) * 32 / 255).checked_sub(23)?;
+            let local = match min_local + (size - min_local) % (usable - 4) {
+                local if local <= max_local => local,
+                _ => min_local,
+            };
+
+            // Overflow pages: 4-byte next page, then the rest of the payload
+            let mut payload = cell.get(..local)?.to_vec();
+            let mut next = u32::from_be_bytes(cell.get(local..local + 4)?.try_into().ok()?);
+            while payload.len() < size {
+                let page = self.page(next as usize)?;
+                let take = (size - payload.len()).min(usable - 4);
+                payload.extend_from_slice(page.get(4..4 + take)?);
+                next = u32::from_be_bytes(page.get(..4)?.try_into().ok()?);
+            }
+
+            Some(payload)
+        }
+
+        // Apply the committed frames of the write-ahead log, pages written since the last
+        // checkpoint are only there (e.g. right after an rpm transaction)
+        fn apply_wal(&mut self, wal: &[u8]) -> Option<()> {
+            let word = |data: &[u8], at: usize| -> Option<u32> {
+                Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?))
+            };
+
+            // Checksums run over 32-bit words in the byte order the magic tells
+            let big_endian = match word(wal, 0)? {
+                0x377f0682 => false,
+                0x377f0683 => true,
+                _ => return None,
+            };
+            let checksum = |data: &[u8], mut sums: (u32, u32)| {
+                for pair in data.chunks_exact(8) {
+                    let (x0, x1) = match big_endian {
+                        true => (
+                            u32::from_be_bytes(pair[..4].try_into().unwrap()),
+                            u32::from_be_bytes(pair[4..].try_into().unwrap()),
+                        ),
+                        false => (
+                            u32::from_le_bytes(pair[..4].try_into().unwrap()),
+                            u32::from_le_bytes(pair[4..].try_into().unwrap()),
+                        ),
+                    };
+                    sums.0 = sums.0.wrapping_add(x0).wrapping_add(sums.1);
+                    sums.1 = sums.1.wrapping_add(x1).wrapping_add(sums.0);
+                }
+                sums
+            };
+
+            let header = wal.get(..32)?;
+            if word(header, 8)? as usize != self.page_size {
+                return None;
+            }
+            let mut sums = checksum(&header[..24], (0, 0));
+            if sums != (word(header, 24)?, word(header, 28)?) {
+                return None;
+            }
+
+            // Frames: page number, database size in pages for commits, salts, checksums
+            let mut pending = Vec::new();
+            let frame_size = 24 + self.page_size;
+            for frame in wal[32..].chunks_exact(frame_size) {
+                if frame[8..16] != header[16..24] {
+                    break;
+                }
+                sums = checksum(&frame[24..], checksum(&frame[..8], sums));
+                if sums != (word(frame, 16)?, word(frame, 20)?) {
+                    break;
+                }
+
+                pending.push((word(frame, 0)? as usize, &frame[24..]));
+
+                // Frames after the last commit belong to an unfinished transaction
+                let pages = word(frame, 4)? as usize;
+                if pages > 0 {
+                    self.data.resize(pages.checked_mul(self.page_size)?, 0);
+                    for (number, page) in pending.drain(..) {
+                        let start = number.checked_sub(1)?.checked_mul(self.page_size)?;
+                        if let Some(target) = self.data.get_mut(start..start + self.page_size) {
+                            target.copy_from_slice(page);
+                        }
+                    }
+                }
+            }
+
+            Some(())
+        }
+    }
+
+    // Decode the first columns of a record as (text, integer) values
+    fn read_record(payload: &[u8], columns: usize) -> Option<Vec<(String, i64)>> {
+        let (header_size, mut at) = read_varint(payload)?;
+        let mut body = header_size as usize;
+        let mut values = Vec::new();
+
+        while at < header_size as usize && values.len() < columns {
+            let (serial, n) = read_varint(payload.get(at..)?)?;
+            at += n;
+
+            let (len, value) = match serial {
+                0 | 8 | 9 => (0, (String::new(), (serial == 9) as i64)),
+                1..=6 => {
+                    let len = [0, 1, 2, 3, 4, 6, 8][serial as usize];
+                    let bytes = payload.get(body..body.checked_add(len)?)?;
+                    let mut int = if bytes[0] & 0x80 != 0 { -1i64 } else { 0 };
+                    for byte in bytes {
+                        int = (int << 8) | *byte as i64;
+                    }
+                    (len, (String::new(), int))
+                }
+                7 => (8, (String::new(), 0)),
+                n if n >= 13 && n % 2 == 1 => {
+                    let len = (n as usize - 13) / 2;
+                    let text = payload
+                        .get(body..body.checked_add(len)?)
+                        .unwrap_or_default();
+                    (len, (String::from_utf8_lossy(text).to_string(), 0))
+                }
+                n => ((n as usize).saturating_sub(12) / 2, (String::new(), 0)),
+            };
+
+            values.push(value);
+            body = body.checked_add(len)?;
+        }
+
+        Some(values)
     }
 
-    return info;
+    pub fn count_rows(path: &str, table: &str) -> Option<usize> {
+        let data = fs::read(path).ok()?;
+        if !data.starts_with(b"SQLite format 3\0") {
+            return None;
+        }
+
+        // A power of two from 512 to 32768, or 1 for 65536
+        let page_size = match u16::from_be_bytes(data.get(16..18)?.try_into().ok()?) {
+            1 => 65536,
+            size if size >= 512 && size.is_power_of_two() => size as usize,
+            _ => return None,
+        };
+        let mut db = Database { data, page_size };
+        if db.usable_size() < 480 {
+            return None;
+        }
+
+        // A log that doesn't apply cleanly leaves the checkpointed pages
+        if let Ok(wal) = fs::read(format!("{}-wal", path)) {
+            if !wal.is_empty() {
+                db.apply_wal(&wal);
+            }
+        }
+
+        // sqlite_master rows: type, name, tbl_name, rootpage, sql
+        let mut root = None;
+        db.walk(1, 0, &mut |cell| {
+            if let Some(columns) = db
+                .payload(cell)
+                .and_then(|payload| read_record(&payload, 4))
+            {
+                if columns.len() == 4 && columns[0].0 == "table" && columns[1].0 == table {
+                    root = Some(columns[3].1 as usize);
+                }
+            }
+        })?;
+
+        let mut count = 0;
+        db.walk(root?, 0, &mut |_| count += 1)?;
+        Some(count)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn fixture(name: &str) -> String {
+            format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+        }
+
+        // Copy fixtures to a scratch directory so the log can be left out or damaged
+        fn scratch(name: &str, files: &[(&str, &[u8])]) -> String {
+            let dir = std::env::temp_dir().join(format!("kifetch-{}-{}", name, std::process::id()));
+            fs::create_dir_all(&dir).unwrap();
+            for (file, data) in files {
+                fs::write(dir.join(file), data).unwrap();
+            }
+            dir.join(files[0].0).to_string_lossy().to_string()
+        }
+
+        #[test]
+        fn varints() {
+            assert_eq!(read_varint(&[0x00]), Some((0, 1)));
+            assert_eq!(read_varint(&[0x7f, 0xff]), Some((127, 1)));
+            assert_eq!(read_varint(&[0x81, 0x00]), Some((128, 2)));
+            assert_eq!(read_varint(&[0x82, 0x80, 0x01]), Some((0x8001, 3)));
+            assert_eq!(read_varint(&[0xff; 9]), Some((u64::MAX, 9)));
+            assert_eq!(read_varint(&[0x81, 0x80]), None);
+            assert_eq!(read_varint(&[]), None);
+        }
+
+        #[test]
+        fn records() {
+            // Header of 4 bytes: NULL, 1-byte int, 5-char text
+            let payload = [0x04, 0x00, 0x01, 0x17, 0xfe, b'h', b'e', b'l', b'l', b'o'];
+            let values = read_record(&payload, 4).unwrap();
+            assert_eq!(values.len(), 3);
+            assert_eq!(values[1].1, -2);
+            assert_eq!(values[2].0, "hello");
+
+            // The integer runs past the end of the payload
+            assert_eq!(read_record(&[0x02, 0x04, 0x00], 1), None);
+        }
+
+        #[test]
+        fn pages() {
+            let db = Database {
+                data: vec![0; 1024],
+                page_size: 512,
+            };
+            assert!(db.page(0).is_none());
+            assert_eq!(db.page(2).map(<[u8]>::len), Some(512));
+            assert!(db.page(3).is_none());
+        }
+
+        // 1000 rows over interior pages, and a table whose schema row overflows
+        #[test]
+        fn count_tables() {
+            let path = fixture("rpmdb.sqlite");
+            assert_eq!(count_rows(&path, "Packages"), Some(1000));
+            assert_eq!(count_rows(&path, "Name"), Some(1000));
+            assert_eq!(count_rows(&path, &"L".repeat(100)), Some(3));
+            assert_eq!(count_rows(&path, "Missing"), None);
+        }
+
+        #[test]
+        fn corrupted_databases() {
+            let data = fs::read(fixture("rpmdb.sqlite")).unwrap();
+
+            let truncated = scratch("truncated", &[("rpmdb.sqlite", &data[..600])]);
+            assert_eq!(count_rows(&truncated, "Packages"), None);
+
+            let mut odd_pages = data.clone();
+            odd_pages[16..18].copy_from_slice(&1000u16.to_be_bytes());
+            let odd_pages = scratch("page-size", &[("rpmdb.sqlite", &odd_pages)]);
+            assert_eq!(count_rows(&odd_pages, "Packages"), None);
+        }
+
+        // 10 checkpointed rows, 500 inserted and 3 deleted in the log, then 300 more
+        // from a transaction that never committed
+        #[test]
+        fn write_ahead_log() {
+            let data = fs::read(fixture("rpmdb-wal.sqlite")).unwrap();
+            let wal = fs::read(fixture("rpmdb-wal.sqlite-wal")).unwrap();
+
+            assert_eq!(
+                count_rows(&fixture("rpmdb-wal.sqlite"), "Packages"),
+                Some(507)
+            );
+
+            let without = scratch("no-wal", &[("rpmdb.sqlite", &data)]);
+            assert_eq!(count_rows(&without, "Packages"), Some(10));
+
+            let empty = scratch(
+                "empty-wal",
+                &[("rpmdb.sqlite", &data), ("rpmdb.sqlite-wal", &[])],
+            );
+            assert_eq!(count_rows(&empty, "Packages"), Some(10));
+
+            // A bad checksum in the first frame drops it and every frame after it
+            let mut damaged = wal.clone();
+            damaged[32 + 24 + 100] ^= 0xff;
+            let damaged = scratch(
+                "damaged-wal",
+                &[("rpmdb.sqlite", &data), ("rpmdb.sqlite-wal", &damaged)],
+            );
+            assert_eq!(count_rows(&damaged, "Packages"), Some(10));
+        }
+    }
+}
+
+// Snap: one directory per snap in /snap, besides /snap/bin
+#[cfg(target_os = "linux")]
+fn count_snap() -> Option<usize> {
+    use std::fs;
+
+    let entries = fs::read_dir("/snap").ok()?;
+    Some(
+        entries
+            .flatten()
+            .filter(|entry| entry.path().is_dir() && entry.file_name() != "bin")
+            .count(),
+    )
+}
+
+// Homebrew on Linux: one directory per formula in the Cellar
+#[cfg(target_os = "linux")]
+fn count_brew() -> Option<usize> {
+    use crate::utils::expand_home;
+
+    ["/home/linuxbrew/.linuxbrew/Cellar", "~/.linuxbrew/Cellar"]
+        .iter()
+        .find_map(|path| count_dir(&expand_home(path), true))
+}
+
+// Flatpak: installed refs (<app|runtime>/<id>/<arch>/<branch>) per installation
+#[cfg(target_os = "linux")]
+fn count_flatpak() -> Vec<(&'static str, usize)> {
+    use crate::utils::expand_home;
+    use std::fs;
+
+    let count_refs = |installation: &str| -> usize {
+        let mut count = 0;
+        for kind in ["app", "runtime"] {
+            let ids = match fs::read_dir(format!("{}/{}", installation, kind)) {
+                Ok(ids) => ids,
+                Err(_) => continue,
+            };
+
+            // <id>/<arch>/<branch>, next to symlinks like <id>/current and <branch>/active
+            let real_dirs = |path: &std::path::Path| {
+                fs::read_dir(path)
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+                    .map(|entry| entry.path())
+                    .collect::<Vec<_>>()
+            };
+
+            for id in ids.flatten() {
+                for arch in real_dirs(&id.path()) {
+                    count += real_dirs(&arch).len();
+                }
+            }
+        }
+        count
+    };
+
+    vec![
+        ("system", count_refs("/var/lib/flatpak")),
+        ("user", count_refs(&expand_home("~/.local/share/flatpak"))),
+    ]
+}
+
+// Nix: store paths in the closure of the system, user and home-manager profiles
+#[cfg(target_os = "linux")]
+fn count_nix() -> Vec<(&'static str, usize)> {
+    use crate::utils::{expand_home, run_output_with_timeout};
+    use std::path::Path;
+    use std::time::Duration;
+
+    let user = std::env::var("USER").unwrap_or_default();
+
+    let count_closure = |profiles: &[String]| -> usize {
+        let profile = match profiles.iter().find(|path| Path::new(path).exists()) {
+            Some(profile) => profile,
+            None => return 0,
+        };
+
+        // Errors go to stderr, only count paths from a successful query
+        run_output_with_timeout(
+            "nix-store",
+            &["--query", "--requisites", profile],
+            Duration::from_secs(2),
+        )
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).lines().count())
+        .unwrap_or(0)
+    };
+
+    vec![
+        (
+            "system",
+            count_closure(&["/run/current-system/sw".to_string()]),
+        ),
+        (
+            "user",
+            count_closure(&[
+                expand_home("~/.nix-profile"),
+                expand_home("~/.local/state/nix/profile"),
+            ]),
+        ),
+        (
+            "home",
+            count_closure(&[
+                format!("/etc/profiles/per-user/{}", user),
+                expand_home("~/.local/state/home-manager/gcroots/current-home"),
+            ]),
+        ),
+    ]
+}
+
+// AppImages in the configured directories
+#[cfg(target_os = "linux")]
+fn count_appimages(dirs: &[String]) -> Option<usize> {
+    use crate::utils::expand_home;
+    use std::fs;
+
+    let mut count = 0;
+    for dir in dirs {
+        if let Ok(entries) = fs::read_dir(expand_home(dir)) {
+            count += entries
+                .flatten()
+                .filter(|entry| {
+                    entry
+                        .file_name()
+                        .to_string_lossy()
+                        .to_lowercase()
+                        .ends_with(".appimage")
+                })
+                .count();
+        }
+    }
+
+    Some(count)
+}
+
+// cargo install: one entry per crate in $CARGO_HOME/.crates.toml
+#[cfg(target_os = "linux")]
+fn count_cargo() -> Option<usize> {
+    use crate::utils::expand_home;
+    use std::{env, fs};
+
+    let cargo_home = env::var("CARGO_HOME").unwrap_or_else(|_| expand_home("~/.cargo"));
+    let crates = fs::read_to_string(format!("{}/.crates.toml", cargo_home)).ok()?;
+    let crates: toml::Value = toml::from_str(&crates).ok()?;
+
+    Some(crates.get("v1")?.as_table()?.len())
+}
+
+// pipx: one virtual environment per application
+#[cfg(target_os = "linux")]
+fn count_pipx() -> Option<usize> {
+    use crate::utils::expand_home;
+    use std::env;
+
+    let pipx_home = env::var("PIPX_HOME").ok();
+    let homes = [
+        pipx_home.map(|home| format!("{}/venvs", home)),
+        Some(expand_home("~/.local/share/pipx/venvs")),
+        Some(expand_home("~/.local/pipx/venvs")),
+    ];
+
+    homes
+        .iter()
+        .flatten()
+        .find_map(|path| count_dir(path, true))
+}
+
+// npm -g: global node_modules, scoped packages live in "@scope" directories
+#[cfg(target_os = "linux")]
+fn count_npm() -> Option<usize> {
+    use crate::utils::{expand_home, get_ini_value};
+    use std::{env, fs};
+
+    let npmrc = fs::read_to_string(expand_home("~/.npmrc")).unwrap_or_default();
+    let prefix = env::var("NPM_CONFIG_PREFIX")
+        .ok()
+        .or_else(|| get_ini_value(&npmrc, "", "prefix"))
+        .map(|prefix| format!("{}/lib/node_modules", expand_home(&prefix)));
+
+    let dirs = [
+        prefix,
+        Some("/usr/local/lib/node_modules".to_string()),
+        Some("/usr/lib/node_modules".to_string()),
+    ];
+
+    let modules = dirs
+        .iter()
+        .flatten()
+        .find_map(|path| fs::read_dir(path).ok())?;
+
+    let mut count = 0;
+    for entry in modules.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('@') {
+            count += count_dir(&entry.path().to_string_lossy(), true).unwrap_or(0);
+        } else if !name.starts_with('.') {
+            count += 1;
+        }
+    }
+
+    Some(count)
+}
+
+#[cfg(target_os = "linux")]
+fn get_packages(config: &PackagesConfig) -> PackagesInfo {
+    use crate::utils::read_os_release;
+
+    let mut info = PackagesInfo::default();
+
+    // Count packages on different package managers
+    for (manager, count) in PACKAGE_MANAGERS {
+        if let Some(count) = count().filter(|count| *count > 0) {
+            info.packages.insert(manager.to_string(), count);
+            info.total_count += count;
+        }
+    }
+
+    // The primary manager is the one of the distribution, e.g. ID_LIKE="rhel fedora"
+    let os_release = read_os_release();
+    let ids = [os_release.get("ID"), os_release.get("ID_LIKE")];
+    let distro_manager = ids
+        .iter()
+        .flatten()
+        .flat_map(|ids| ids.split_whitespace())
+        .filter_map(|id| DISTRO_MANAGERS.get(id))
+        .find(|manager| info.packages.contains_key(**manager));
+
+    // Otherwise the one with the most packages
+    let primary = match distro_manager {
+        Some(manager) => Some(manager.to_string()),
+        None => info
+            .packages
+            .iter()
+            .max_by_key(|(_, count)| **count)
+            .map(|(manager, _)| manager.clone()),
+    };
+
+    if let Some(manager) = primary {
+        info.count = info.packages[&manager];
+        info.manager = manager;
+    }
+
+    // Universal package managers, never the primary one
+    let mut extra: Vec<(&str, Option<usize>)> = vec![
+        ("snap", count_snap()),
+        ("brew", count_brew()),
+        ("appimage", count_appimages(&config.appimage_dirs)),
+    ];
+
+    // Flatpak and nix have system and per-user installations
+    for (manager, scopes) in [("flatpak", count_flatpak()), ("nix", count_nix())] {
+        let total: usize = scopes.iter().map(|(_, count)| count).sum();
+        for (scope, count) in scopes.into_iter().filter(|(_, count)| *count > 0) {
+            info.scopes.insert(format!("{}_{}", manager, scope), count);
+        }
+        extra.push((manager, Some(total)));
+    }
+
+    // Language package managers are opt-in
+    for language in &config.languages {
+        let count = match language.as_str() {
+            "cargo" => count_cargo(),
+            "pipx" => count_pipx(),
+            "npm" => count_npm(),
+            _ => None,
+        };
+        extra.push((language, count));
+    }
+
+    for (manager, count) in extra {
+        if let Some(count) = count.filter(|count| *count > 0) {
+            info.packages.insert(manager.to_string(), count);
+            info.total_count += count;
+        }
+    }
+
+    info
 }
 
 #[cfg(target_os = "windows")]
-fn get_packages() -> PackagesInfo {
+fn get_packages(_config: &PackagesConfig) -> PackagesInfo {
     use winreg::{enums::HKEY_LOCAL_MACHINE, RegKey};
 
     let mut info = PackagesInfo::default();
@@ -79,11 +820,12 @@ fn get_packages() -> PackagesInfo {
 
     info.manager = "registry".to_string();
     info.total_count = info.count;
-    return info;
+    info.packages.insert(info.manager.clone(), info.count);
+    info
 }
 
-pub fn load_packages_info(info: &mut SystemInfo) {
-    let packages = get_packages();
+pub fn load_packages_info(config: &PackagesConfig, info: &mut SystemInfo) {
+    let packages = get_packages(config);
 
     info.insert(
         "packages_total".to_string(),
@@ -97,4 +839,18 @@ pub fn load_packages_info(info: &mut SystemInfo) {
     for (manager, count) in packages.packages.iter() {
         info.insert(format!("packages_{}", manager), count.to_string());
     }
+
+    for (scope, count) in packages.scopes.iter() {
+        info.insert(format!("packages_{}", scope), count.to_string());
+    }
+
+    // Summary, e.g. "1234 (dpkg), 12 (flatpak), 5 (snap)"
+    let mut managers: Vec<(&String, &usize)> = packages.packages.iter().collect();
+    managers.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+
+    let summary: Vec<String> = managers
+        .iter()
+        .map(|(manager, count)| format!("{} ({})", count, manager))
+        .collect();
+    info.insert("packages_summary".to_string(), summary.join(", "));
 }
@@ -0,0 +1,270 @@
+use super::SystemInfo;
+use crate::utils::is_placeholder;
+
+// A memory slot from an SMBIOS type 17 (Memory Device) structure
+struct MemoryDevice {
+    slot: String,         // Device locator (e.g. DIMM_A1)
+    size: u64,            // Size in MB, 0 when the slot is empty
+    kind: String,         // Memory type (e.g. DDR5)
+    speed: u32,           // Configured speed in MT/s, 0 when unknown
+    manufacturer: String, // Module manufacturer
+    part_number: String,  // Module part number
+}
+
+// SMBIOS memory types (type 17, offset 0x12)
+const MEMORY_TYPES: phf::Map<u8, &'static str> = phf::phf_map! {
+    0x03u8 => "DRAM",
+    0x07u8 => "RAM",
+    0x0Fu8 => "SDRAM",
+    0x11u8 => "RDRAM",
+    0x12u8 => "DDR",
+    0x13u8 => "DDR2",
+    0x14u8 => "DDR2 FB-DIMM",
+    0x18u8 => "DDR3",
+    0x1Au8 => "DDR4",
+    0x1Bu8 => "LPDDR",
+    0x1Cu8 => "LPDDR2",
+    0x1Du8 => "LPDDR3",
+    0x1Eu8 => "LPDDR4",
+    0x20u8 => "HBM",
+    0x21u8 => "HBM2",
+    0x22u8 => "DDR5",
+    0x23u8 => "LPDDR5",
+    0x24u8 => "HBM3",
+};
+
+// Some firmware reports the JEDEC manufacturer id instead of a name (e.g. "80CE000080CE")
+const JEDEC_MANUFACTURERS: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "80CE" => "Samsung",
+    "80AD" => "SK Hynix",
+    "802C" => "Micron",
+    "859B" => "Crucial",
+    "0198" => "Kingston",
+    "04CD" => "G.Skill",
+    "029E" => "Corsair",
+    "04F1" => "Team Group",
+    "0443" => "Ramaxel",
+    "8551" => "Qimonda",
+    "80C2" => "Nanya",
+    "8632" => "ADATA",
+    "04CB" => "ADATA",
+};
+
+// Strings follow the formatted area, numbered from 1 and ended by an empty string
+fn get_string(strings: &[&[u8]], index: u8) -> String {
+    match index {
+        0 => String::new(),
+        i => strings
+            .get(i as usize - 1)
+            .map(|s| String::from_utf8_lossy(s).trim().to_string())
+            .unwrap_or_default(),
+    }
+}
+
+fn get_manufacturer(value: &str) -> String {
+    let id = value.get(..4).unwrap_or_default().to_uppercase();
+    JEDEC_MANUFACTURERS
+        .get(id.as_str())
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| value.to_string())
+}
+
+// Walk the SMBIOS structure table and collect every memory device
+fn parse_memory_devices(table: &[u8]) -> Vec<MemoryDevice> {
+    let mut devices = Vec::new();
+    let mut offset = 0;
+
+    while offset + 4 <= table.len() {
+        let kind = table[offset];
+        let length = table[offset + 1] as usize;
+        if length < 4 || offset + length > table.len() {
+            break;
+        }
+
+        let data = &table[offset..offset + length];
+
+        // The string set ends with two null bytes
+        let mut end = offset + length;
+        while end + 1 < table.len() && !(table[end] == 0 && table[end + 1] == 0) {
+            end += 1;
+        }
+        let strings: Vec<&[u8]> = table[offset + length..end]
+            .split(|&b| b == 0)
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        // Type 127 marks the end of the table
+        if kind == 127 {
+            break;
+        }
+
+        if kind == 17 && length >= 0x15 {
+            let word = |at: usize| {
+                data.get(at..at + 2)
+                    .map(|b| u16::from_le_bytes([b[0], b[1]]))
+                    .unwrap_or(0)
+            };
+            let dword = |at: usize| {
+                data.get(at..at + 4)
+                    .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                    .unwrap_or(0)
+            };
+            let byte = |at: usize| data.get(at).copied().unwrap_or(0);
+
+            // Bit 15 selects KB granularity, 0x7FFF defers to the extended size
+            let size = match word(0x0C) {
+                0xFFFF => 0,
+                0x7FFF => (dword(0x1C) & 0x7FFF_FFFF) as u64,
+                size if size & 0x8000 != 0 => (size & 0x7FFF) as u64 / 1024,
+                size => size as u64,
+            };
+
+            // Configured speed, falling back to the maximum speed
+            // 0xFFFF defers to the 32-bit extended fields of SMBIOS 3.3
+            let speed = [(0x20, 0x58), (0x15, 0x54)]
+                .iter()
+                .map(|&(at, extended)| match word(at) {
+                    0xFFFF => dword(extended),
+                    speed => speed as u32,
+                })
+                .find(|&speed| speed != 0)
+                .unwrap_or(0);
+
+            let manufacturer = get_string(&strings, byte(0x17));
+            let part_number = get_string(&strings, byte(0x1A));
+
+            devices.push(MemoryDevice {
+                slot: get_string(&strings, byte(0x10)),
+                size,
+                kind: MEMORY_TYPES
+                    .get(&byte(0x12))
+                    .map(|kind| kind.to_string())
+                    .unwrap_or_else(|| "Unknown".to_string()),
+                speed,
+                manufacturer: if is_placeholder(&manufacturer) {
+                    "Unknown".to_string()
+                } else {
+                    get_manufacturer(&manufacturer)
+                },
+                part_number: if is_placeholder(&part_number) {
+                    "Unknown".to_string()
+                } else {
+                    part_number
+                },
+            });
+        }
+
+        offset = end + 2;
+    }
+
+    devices
+}
+
+#[cfg(target_os = "linux")]
+fn get_smbios_table() -> Result<Vec<u8>, String> {
+    use std::fs;
+    use std::io::ErrorKind;
+
+    fs::read("/sys/firmware/dmi/tables/DMI").map_err(|e| match e.kind() {
+        ErrorKind::PermissionDenied => "Unreadable (requires root)".to_string(),
+        _ => "Unavailable".to_string(),
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn get_smbios_table() -> Result<Vec<u8>, String> {
+    use windows::Win32::System::SystemInformation::{
+        GetSystemFirmwareTable, FIRMWARE_TABLE_PROVIDER,
+    };
+
+    // 'RSMB' provider, the table follows an 8 byte RawSMBIOSData header
+    let provider = FIRMWARE_TABLE_PROVIDER(u32::from_be_bytes(*b"RSMB"));
+
+    let size = unsafe { GetSystemFirmwareTable(provider, 0, None) };
+    if size == 0 {
+        return Err("Unavailable".to_string());
+    }
+
+    let mut buffer = vec![0u8; size as usize];
+    let read = unsafe { GetSystemFirmwareTable(provider, 0, Some(&mut buffer)) };
+    if read == 0 || buffer.len() < 8 {
+        return Err("Unavailable".to_string());
+    }
+
+    Ok(buffer.split_off(8))
+}
+
+fn format_module_size(mb: u64) -> String {
+    if mb >= 1024 && mb % 1024 == 0 {
+        format!("{} GB", mb / 1024)
+    } else {
+        format!("{} MB", mb)
+    }
+}
+
+pub fn load_ram_info(info: &mut SystemInfo) {
+    let devices = match get_smbios_table() {
+        Ok(table) => {
+            info.insert("ram_status".to_string(), "OK".to_string());
+            parse_memory_devices(&table)
+        }
+        Err(status) => {
+            info.insert("ram_status".to_string(), status.clone());
+            info.insert("ram_summary".to_string(), status);
+            return;
+        }
+    };
+
+    let installed: Vec<&MemoryDevice> = devices.iter().filter(|d| d.size > 0).collect();
+    let total: u64 = installed.iter().map(|d| d.size).sum();
+
+    info.insert("ram_slots".to_string(), devices.len().to_string());
+    info.insert("ram_slots_used".to_string(), installed.len().to_string());
+    info.insert("ram_count".to_string(), installed.len().to_string());
+    info.insert("ram_total".to_string(), format_module_size(total));
+
+    for (i, device) in installed.iter().enumerate() {
+        let prefix = format!("ram_{}", i);
+        info.insert(format!("{}_slot", prefix), device.slot.clone());
+        info.insert(format!("{}_size", prefix), format_module_size(device.size));
+        info.insert(format!("{}_type", prefix), device.kind.clone());
+        info.insert(format!("{}_speed", prefix), device.speed.to_string());
+        info.insert(
+            format!("{}_manufacturer", prefix),
+            device.manufacturer.clone(),
+        );
+        info.insert(
+            format!("{}_part_number", prefix),
+            device.part_number.clone(),
+        );
+    }
+
+    // Identical modules are grouped, e.g. "2x 16 GB DDR5 @ 5600 MT/s (2 of 4 slots)"
+    let mut groups: Vec<(String, usize)> = Vec::new();
+    for device in &installed {
+        let mut description = format!("{} {}", format_module_size(device.size), device.kind);
+        if device.speed > 0 {
+            description.push_str(&format!(" @ {} MT/s", device.speed));
+        }
+
+        match groups.iter_mut().find(|(d, _)| *d == description) {
+            Some((_, count)) => *count += 1,
+            None => groups.push((description, 1)),
+        }
+    }
+
+    let modules: Vec<String> = groups
+        .iter()
+        .map(|(description, count)| format!("{}x {}", count, description))
+        .collect();
+
+    info.insert(
+        "ram_summary".to_string(),
+        format!(
+            "{} ({} of {} slots)",
+            modules.join(", "),
+            installed.len(),
+            devices.len()
+        ),
+    );
+}
@@ -1,80 +1,187 @@
 use super::SystemInfo;
 
 // ScreenInfo struct to hold display information
-// Consider adding doc comments (///) to document each field
+#[derive(Default)]
 struct ScreenInfo {
+    name: String,        // Connector name (e.g. HDMI-A-1)
+    vendor: String,      // Monitor vendor from EDID
+    model: String,       // Monitor model name from EDID
     width: usize,        // Screen width in pixels
     height: usize,       // Screen height in pixels
     refresh_rate: usize, // Refresh rate in Hz
+    width_mm: usize,     // Physical width in millimeters
+    height_mm: usize,    // Physical height in millimeters
 }
 
-impl Default for ScreenInfo {
-    fn default() -> Self {
-        ScreenInfo {
-            width: 0,
-            height: 0,
-            refresh_rate: 0,
+// EDID (PNP) manufacturer IDs
+#[cfg(target_os = "linux")]
+const EDID_VENDORS: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "ACR" => "Acer",
+    "AOC" => "AOC",
+    "APP" => "Apple",
+    "AUO" => "AU Optronics",
+    "AUS" => "ASUS",
+    "BNQ" => "BenQ",
+    "BOE" => "BOE",
+    "CMN" => "Chimei Innolux",
+    "DEL" => "Dell",
+    "EIZ" => "EIZO",
+    "GBT" => "Gigabyte",
+    "GSM" => "LG",
+    "HWP" => "HP",
+    "IVM" => "Iiyama",
+    "LEN" => "Lenovo",
+    "LGD" => "LG Display",
+    "MSI" => "MSI",
+    "NEC" => "NEC",
+    "PHL" => "Philips",
+    "RHT" => "Red Hat",
+    "SAM" => "Samsung",
+    "SDC" => "Samsung Display",
+    "SHP" => "Sharp",
+    "SNY" => "Sony",
+    "VSC" => "ViewSonic",
+};
+
+// Fill vendor, model, physical size and preferred mode from an EDID blob
+#[cfg(target_os = "linux")]
+fn parse_edid(edid: &[u8], screen: &mut ScreenInfo) {
+    const HEADER: [u8; 8] = [0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00];
+
+    if edid.len() < 128 || edid[..8] != HEADER {
+        return;
+    }
+
+    // Manufacturer ID: three 5-bit letters, 'A' = 1
+    let id = u16::from_be_bytes([edid[8], edid[9]]);
+    let vendor: String = [10, 5, 0]
+        .iter()
+        .map(|shift| (((id >> shift) & 0x1f) as u8 + b'A' - 1) as char)
+        .collect();
+    screen.vendor = EDID_VENDORS
+        .get(vendor.as_str())
+        .map(|v| v.to_string())
+        .unwrap_or(vendor);
+
+    // Screen size in centimeters
+    screen.width_mm = edid[21] as usize * 10;
+    screen.height_mm = edid[22] as usize * 10;
+
+    // Four 18-byte descriptors
+    for (i, descriptor) in edid[54..126].chunks(18).enumerate() {
+        let pixel_clock = u16::from_le_bytes([descriptor[0], descriptor[1]]) as usize;
+
+        if pixel_clock == 0 {
+            // Display descriptor, 0xfc is the monitor name
+            if descriptor[3] == 0xfc {
+                screen.model = String::from_utf8_lossy(&descriptor[5..])
+                    .split('\n')
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string();
+            }
+            continue;
+        }
+
+        // The first detailed timing is the preferred mode
+        if i == 0 {
+            let h_active = descriptor[2] as usize | ((descriptor[4] as usize >> 4) << 8);
+            let h_blank = descriptor[3] as usize | ((descriptor[4] as usize & 0x0f) << 8);
+            let v_active = descriptor[5] as usize | ((descriptor[7] as usize >> 4) << 8);
+            let v_blank = descriptor[6] as usize | ((descriptor[7] as usize & 0x0f) << 8);
+
+            screen.width = h_active;
+            screen.height = v_active;
+
+            let total = (h_active + h_blank) * (v_active + v_blank);
+            if total > 0 {
+                // Pixel clock is in units of 10 kHz
+                screen.refresh_rate =
+                    (pixel_clock as f64 * 10_000.0 / total as f64).round() as usize;
+            }
+
+            // Image size in millimeters is more precise than the cm fields
+            let width_mm = descriptor[12] as usize | ((descriptor[14] as usize >> 4) << 8);
+            let height_mm = descriptor[13] as usize | ((descriptor[14] as usize & 0x0f) << 8);
+            if width_mm > 0 && height_mm > 0 {
+                screen.width_mm = width_mm;
+                screen.height_mm = height_mm;
+            }
         }
     }
 }
 
 #[cfg(target_os = "linux")]
-fn get_screen() -> ScreenInfo {
-    use std::process::Command;
+fn get_screens() -> Vec<ScreenInfo> {
+    use std::fs;
+    use std::path::Path;
 
-    // Execute xrandr command to get display information
-    let output = Command::new("xrandr")
-        .arg("--current")
-        .output()
-        .expect("Failed to execute xrandr");
+    let mut screens = Vec::new();
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut lines = stdout.lines();
+    // Connectors look like "card0-HDMI-A-1"
+    let mut connectors: Vec<String> = match fs::read_dir("/sys/class/drm") {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| name.starts_with("card") && name.contains('-'))
+            .collect(),
+        Err(_) => return screens,
+    };
+    // By card, connector type and numeric index, so "DP-10" comes after "DP-2"
+    connectors.sort_by_key(|name| {
+        let (card, connector) = name.split_once('-').unwrap_or((name, ""));
+        let (kind, index) = connector.rsplit_once('-').unwrap_or((connector, ""));
+        (
+            card[4..].parse::<usize>().unwrap_or(0),
+            kind.to_string(),
+            index.parse::<usize>().unwrap_or(0),
+        )
+    });
 
-    let mut found_connected = false;
+    for connector in connectors {
+        let path = Path::new("/sys/class/drm").join(&connector);
 
-    while let Some(line) = lines.next() {
-        // Look for connected display
-        if line.contains(" connected ") {
-            found_connected = true;
+        let status = fs::read_to_string(path.join("status")).unwrap_or_default();
+        if status.trim() != "connected" {
             continue;
         }
 
-        // If we're in a connected display block, look for resolution info
-        if found_connected && line.trim().starts_with(char::is_numeric) {
-            // Line format example: "   1920x1080     59.96*+"
-            let parts: Vec<&str> = line.trim().split_whitespace().collect();
-            if parts.len() >= 2 && parts[1].contains('*') {
-                let res_parts: Vec<&str> = parts[0].split('x').collect();
-                if res_parts.len() == 2 {
-                    if let (Ok(w), Ok(h)) =
-                        (res_parts[0].parse::<usize>(), res_parts[1].parse::<usize>())
-                    {
-                        // Clean up refresh rate string (remove * and +)
-                        let rate_str = parts[1].trim_end_matches(|c| c == '*' || c == '+');
-                        if let Ok(rate) = rate_str.parse::<f32>() {
-                            return ScreenInfo {
-                                width: w,
-                                height: h,
-                                refresh_rate: rate.round() as usize,
-                            };
-                        }
+        let mut screen = ScreenInfo {
+            name: connector
+                .split_once('-')
+                .map(|(_, name)| name.to_string())
+                .unwrap_or(connector.clone()),
+            ..Default::default()
+        };
+
+        if let Ok(edid) = fs::read(path.join("edid")) {
+            parse_edid(&edid, &mut screen);
+        }
+
+        // The first mode is the preferred one, e.g. "1920x1080"
+        if let Ok(modes) = fs::read_to_string(path.join("modes")) {
+            if let Some((w, h)) = modes.lines().next().and_then(|mode| mode.split_once('x')) {
+                let h = h.trim_end_matches(|c: char| !c.is_ascii_digit());
+                if let (Ok(w), Ok(h)) = (w.parse::<usize>(), h.parse::<usize>()) {
+                    // The EDID refresh rate belongs to a different mode
+                    if (w, h) != (screen.width, screen.height) {
+                        screen.refresh_rate = 0;
                     }
+                    screen.width = w;
+                    screen.height = h;
                 }
             }
         }
 
-        // If we encounter a disconnected display, exit the block
-        if line.contains("disconnected") {
-            found_connected = false;
-        }
+        screens.push(screen);
     }
 
-    ScreenInfo::default()
+    screens
 }
 
 #[cfg(target_os = "windows")]
-fn get_screen() -> ScreenInfo {
+fn get_screens() -> Vec<ScreenInfo> {
     use windows::Win32::Graphics::Gdi::{EnumDisplaySettingsW, DEVMODEW, ENUM_CURRENT_SETTINGS};
 
     let mut devmode = DEVMODEW::default();
@@ -82,26 +189,86 @@ fn get_screen() -> ScreenInfo {
 
     unsafe {
         if EnumDisplaySettingsW(None, ENUM_CURRENT_SETTINGS, &mut devmode).as_bool() {
-            return ScreenInfo {
+            return vec![ScreenInfo {
                 width: devmode.dmPelsWidth as usize,
                 height: devmode.dmPelsHeight as usize,
                 refresh_rate: devmode.dmDisplayFrequency as usize,
-            };
+                ..Default::default()
+            }];
         }
     }
 
-    ScreenInfo::default()
+    Vec::new()
 }
 
-/// Loads screen information into the SystemInfo structure
-/// Adds screen_width, screen_height, and screen_refresh_rate fields
-pub fn load_screen_info(info: &mut SystemInfo) {
-    let screen = get_screen();
+// Short description, e.g. "Dell U2720Q 3840x2160 @ 60 Hz"
+fn describe_screen(screen: &ScreenInfo) -> String {
+    let mut description = String::new();
+
+    if !screen.model.is_empty() {
+        let model = screen.model.to_lowercase();
+        if !model.starts_with(&screen.vendor.to_lowercase()) {
+            description.push_str(&format!("{} ", screen.vendor));
+        }
+        description.push_str(&format!("{} ", screen.model));
+    } else if !screen.name.is_empty() {
+        description.push_str(&format!("{} ", screen.name));
+    }
+
+    description.push_str(&format!("{}x{}", screen.width, screen.height));
 
-    info.insert("screen_width".to_string(), screen.width.to_string());
-    info.insert("screen_height".to_string(), screen.height.to_string());
+    if screen.refresh_rate > 0 {
+        description.push_str(&format!(" @ {} Hz", screen.refresh_rate));
+    }
+
+    description
+}
+
+fn insert_screen(prefix: &str, screen: &ScreenInfo, info: &mut SystemInfo) {
+    info.insert(format!("{}_name", prefix), screen.name.clone());
+    info.insert(format!("{}_vendor", prefix), screen.vendor.clone());
+    info.insert(format!("{}_model", prefix), screen.model.clone());
+    info.insert(format!("{}_width", prefix), screen.width.to_string());
+    info.insert(format!("{}_height", prefix), screen.height.to_string());
     info.insert(
-        "screen_refresh_rate".to_string(),
+        format!("{}_refresh_rate", prefix),
         screen.refresh_rate.to_string(),
     );
+
+    // Projectors and some TVs leave the physical size empty
+    if screen.width_mm > 0 && screen.height_mm > 0 {
+        info.insert(
+            format!("{}_physical", prefix),
+            format!("{}x{} mm", screen.width_mm, screen.height_mm),
+        );
+
+        let diagonal_mm = ((screen.width_mm.pow(2) + screen.height_mm.pow(2)) as f64).sqrt();
+        info.insert(
+            format!("{}_size", prefix),
+            format!("{:.1}", diagonal_mm / 25.4),
+        );
+    } else {
+        info.insert(format!("{}_physical", prefix), "Unknown".to_string());
+        info.insert(format!("{}_size", prefix), "Unknown".to_string());
+    }
+}
+
+/// Loads screen information into the SystemInfo structure
+/// Adds screen_* fields for the primary display, screen_<n>_* fields for
+/// every connected display and a combined screen_summary
+pub fn load_screen_info(info: &mut SystemInfo) {
+    let screens = get_screens();
+
+    match screens.first() {
+        Some(screen) => insert_screen("screen", screen, info),
+        None => insert_screen("screen", &ScreenInfo::default(), info),
+    }
+
+    info.insert("screen_count".to_string(), screens.len().to_string());
+    for (i, screen) in screens.iter().enumerate() {
+        insert_screen(&format!("screen_{}", i), screen, info);
+    }
+
+    let summary: Vec<String> = screens.iter().map(describe_screen).collect();
+    info.insert("screen_summary".to_string(), summary.join(", "));
 }
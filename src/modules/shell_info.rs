@@ -0,0 +1,204 @@
+use std::time::Duration;
+
+use super::SystemInfo;
+
+// Maximum time to wait for a shell to print its version
+const VERSION_TIMEOUT: Duration = Duration::from_millis(500);
+
+// Known shells and the arguments that make them print their version
+const SHELLS: &[(&str, &[&str])] = &[
+    ("bash", &["--version"]),
+    ("zsh", &["--version"]),
+    ("fish", &["--version"]),
+    ("nu", &["--version"]),
+    ("elvish", &["-version"]),
+    ("ksh", &["--version"]),
+    ("mksh", &["-c", "echo ${KSH_VERSION#*KSH R}"]), // "@(#)MIRBSD KSH R59 2020/10/31"
+    ("oksh", &["-c", "echo $KSH_VERSION"]),
+    ("tcsh", &["--version"]),
+    ("csh", &["--version"]),
+    ("xonsh", &["--version"]),
+    ("yash", &["--version"]),
+    ("osh", &["--version"]),
+    ("ysh", &["--version"]),
+    ("ion", &["--version"]),
+    ("pwsh", &["--version"]),
+    (
+        "powershell",
+        &[
+            "-NoProfile",
+            "-Command",
+            "$PSVersionTable.PSVersion.ToString()",
+        ],
+    ),
+    ("cmd", &["/c", "ver"]),
+    ("dash", &[]), // dash has no version flag
+    ("ash", &[]),
+    ("sh", &[]),
+];
+
+struct ShellInfo {
+    name: String,    // Shell name (e.g. bash, zsh)
+    version: String, // Shell version
+    path: String,    // Shell executable path
+}
+
+impl Default for ShellInfo {
+    fn default() -> Self {
+        ShellInfo {
+            name: "Unknown".to_string(),
+            version: "Unknown".to_string(),
+            path: "Unknown".to_string(),
+        }
+    }
+}
+
+// The shell kifetch was started from, skipping wrappers like sudo
+#[cfg(target_os = "linux")]
+fn get_running_shell() -> Option<(String, String)> {
+    use crate::utils::{get_parent_pid, get_process_name};
+    use std::fs;
+
+    let mut pid = get_parent_pid(std::process::id())?;
+
+    while pid > 1 {
+        if let Ok(exe) = fs::read_link(format!("/proc/{}/exe", pid)) {
+            let mut name = exe
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            // Interpreted shells (e.g. xonsh) run as python
+            if name.starts_with("python") {
+                name = get_process_name(pid).unwrap_or(name);
+            }
+
+            if SHELLS.iter().any(|(shell, _)| *shell == name) {
+                return Some((name, exe.to_string_lossy().to_string()));
+            }
+        }
+
+        pid = get_parent_pid(pid)?;
+    }
+
+    None
+}
+
+// Full path of a running process, e.g. C:\Program Files\PowerShell\7\pwsh.exe
+#[cfg(target_os = "windows")]
+fn get_process_path(pid: u32) -> Option<String> {
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+
+        let mut buffer = [0u16; 1024];
+        let mut size = buffer.len() as u32;
+        let result = QueryFullProcessImageNameW(
+            handle,
+            PROCESS_NAME_WIN32,
+            PWSTR(buffer.as_mut_ptr()),
+            &mut size,
+        );
+        let _ = CloseHandle(handle);
+        result.ok()?;
+
+        Some(String::from_utf16_lossy(&buffer[..size as usize]))
+    }
+}
+
+// The shell kifetch was started from, found by walking up the parent processes
+#[cfg(target_os = "windows")]
+fn get_running_shell() -> Option<(String, String)> {
+    use std::collections::HashMap;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W,
+        TH32CS_SNAPPROCESS,
+    };
+
+    // Process id -> (parent id, executable name)
+    let mut processes = HashMap::new();
+
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0).ok()?;
+
+        let mut entry = PROCESSENTRY32W {
+            dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+            ..Default::default()
+        };
+        let mut found = Process32FirstW(snapshot, &mut entry).is_ok();
+        while found {
+            let len = entry
+                .szExeFile
+                .iter()
+                .position(|&c| c == 0)
+                .unwrap_or(entry.szExeFile.len());
+            let exe = String::from_utf16_lossy(&entry.szExeFile[..len]);
+            processes.insert(entry.th32ProcessID, (entry.th32ParentProcessID, exe));
+
+            found = Process32NextW(snapshot, &mut entry).is_ok();
+        }
+
+        let _ = CloseHandle(snapshot);
+    }
+
+    // Parent ids are reused once the parent exits, so the depth is capped
+    let mut pid = std::process::id();
+    for _ in 0..32 {
+        pid = processes.get(&pid)?.0;
+        let (_, exe) = processes.get(&pid)?;
+
+        let exe = exe.to_lowercase();
+        let name = exe.strip_suffix(".exe").unwrap_or(&exe);
+
+        if SHELLS.iter().any(|(shell, _)| *shell == name) {
+            let path = get_process_path(pid).unwrap_or_else(|| exe.clone());
+            return Some((name.to_string(), path));
+        }
+    }
+
+    None
+}
+
+fn get_shell() -> ShellInfo {
+    use crate::utils::{parse_version, run_with_timeout};
+
+    let mut shell = ShellInfo::default();
+
+    let (name, path) = match get_running_shell() {
+        Some(found) => found,
+        None => return shell,
+    };
+
+    let args = SHELLS
+        .iter()
+        .find(|(shell, _)| *shell == name)
+        .map(|(_, args)| *args)
+        .unwrap_or_default();
+
+    if !args.is_empty() {
+        if let Some(version) =
+            run_with_timeout(&path, args, VERSION_TIMEOUT).and_then(|out| parse_version(&out))
+        {
+            shell.version = version;
+        }
+    }
+
+    shell.name = name;
+    shell.path = path;
+    shell
+}
+
+pub fn load_shell_info(info: &mut SystemInfo) {
+    let shell = get_shell();
+
+    info.insert("shell_name".to_string(), shell.name);
+    info.insert("shell_version".to_string(), shell.version);
+    info.insert("shell_path".to_string(), shell.path);
+}
@@ -0,0 +1,313 @@
+use super::SystemInfo;
+use crate::config::StorageConfig;
+
+#[derive(Default)]
+struct StorageInfo {
+    name: String,            // Kernel name (e.g. nvme0n1, sda)
+    model: String,           // Device model
+    vendor: String,          // Device vendor
+    serial: String,          // Serial number
+    size: u64,               // Size in bytes
+    rotational: bool,        // Spinning disk
+    transport: String,       // NVMe, SATA, USB, virtio, ...
+    partition_table: String, // gpt, dos or none
+}
+
+// Virtual block devices, only listed when requested
+#[cfg(target_os = "linux")]
+const VIRTUAL_PREFIXES: &[&str] = &["loop", "ram", "dm-", "zram", "md", "sr", "fd", "nbd"];
+
+// Partition table from the first sectors of the disk, for systems without udev
+// (e.g. containers). Needs read access to the device, usually root or the disk group
+#[cfg(target_os = "linux")]
+fn read_partition_table(name: &str, sector_size: usize) -> Option<&'static str> {
+    use std::fs::File;
+    use std::io::Read;
+
+    let mut sectors = vec![0; sector_size * 2];
+    File::open(format!("/dev/{}", name))
+        .ok()?
+        .read_exact(&mut sectors)
+        .ok()?;
+
+    // The GPT header is in LBA 1, behind a protective MBR
+    if sectors[sector_size..].starts_with(b"EFI PART") {
+        return Some("gpt");
+    }
+    if sectors[510..512] != [0x55, 0xaa] {
+        return Some("none");
+    }
+
+    // Four 16-byte entries at 446: boot flag 0x00 or 0x80 and a partition type, so
+    // boot sectors of unpartitioned (superfloppy) FAT disks don't count
+    let entries: Vec<&[u8]> = sectors[446..510].chunks_exact(16).collect();
+    if entries.iter().any(|entry| entry[4] == 0xee) {
+        Some("gpt")
+    } else if entries.iter().all(|entry| entry[0] & 0x7f == 0)
+        && entries.iter().any(|entry| entry[4] != 0)
+    {
+        Some("dos")
+    } else {
+        Some("none")
+    }
+}
+
+// Disks are sold in decimal units, e.g. 1000204886016 bytes -> "1TB"
+fn format_disk_size(bytes: u64) -> String {
+    let units = [("TB", 1e12), ("GB", 1e9), ("MB", 1e6), ("KB", 1e3)];
+
+    for (unit, size) in units {
+        let value = bytes as f64 / size;
+        if value >= 1.0 {
+            let value = format!("{:.1}", value);
+            return format!("{}{}", value.trim_end_matches(".0"), unit);
+        }
+    }
+
+    format!("{}B", bytes)
+}
+
+// Keep the first characters of the serial, e.g. "S6B2**********"
+fn redact_serial(serial: &str) -> String {
+    serial
+        .chars()
+        .enumerate()
+        .map(|(i, c)| if i < 4 { c } else { '*' })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn get_storage(config: &StorageConfig) -> Vec<StorageInfo> {
+    use crate::utils::parse_key_values;
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::Path;
+
+    fn read_trimmed(path: &Path) -> Option<String> {
+        fs::read_to_string(path)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    // udev keeps properties like ID_SERIAL_SHORT and ID_PART_TABLE_TYPE
+    // in /run/udev/data/b<major>:<minor>
+    fn read_udev(block: &Path) -> HashMap<String, String> {
+        read_trimmed(&block.join("dev"))
+            .and_then(|dev| fs::read_to_string(format!("/run/udev/data/b{}", dev)).ok())
+            .map(|data| {
+                let properties: String = data
+                    .lines()
+                    .filter_map(|line| line.strip_prefix("E:"))
+                    .map(|line| format!("{}\n", line))
+                    .collect();
+                parse_key_values(&properties)
+            })
+            .unwrap_or_default()
+    }
+
+    let mut devices = Vec::new();
+
+    let mut names: Vec<String> = match fs::read_dir("/sys/block") {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| {
+                config.include_virtual
+                    || !VIRTUAL_PREFIXES
+                        .iter()
+                        .any(|prefix| name.starts_with(prefix))
+            })
+            .collect(),
+        Err(_) => return devices,
+    };
+    names.sort();
+
+    for name in names {
+        let block = Path::new("/sys/block").join(&name);
+        let device = block.join("device");
+
+        let udev = read_udev(&block);
+
+        let mut storage = StorageInfo {
+            name: name.clone(),
+            model: read_trimmed(&device.join("model"))
+                .or_else(|| udev.get("ID_MODEL").map(|m| m.replace('_', " ")))
+                .unwrap_or_else(|| "Unknown".to_string()),
+            // virtio reports its PCI vendor id (e.g. "0x1af4") instead of a name
+            vendor: read_trimmed(&device.join("vendor"))
+                .filter(|vendor| !vendor.starts_with("0x"))
+                .unwrap_or_default(),
+            size: read_trimmed(&block.join("size"))
+                .and_then(|sectors| sectors.parse::<u64>().ok())
+                .unwrap_or(0)
+                * 512,
+            rotational: read_trimmed(&block.join("queue/rotational")).as_deref() == Some("1"),
+            ..Default::default()
+        };
+
+        // Empty loop devices and card readers without media
+        if storage.size == 0 {
+            continue;
+        }
+
+        // SATA disks report "ATA" as the vendor, the real one starts the model
+        if storage.vendor.is_empty() || storage.vendor == "ATA" {
+            storage.vendor = storage
+                .model
+                .split_whitespace()
+                .next()
+                .unwrap_or_default()
+                .to_string();
+        }
+
+        // Serial number, SCSI/SATA devices expose it through VPD page 0x80
+        storage.serial = read_trimmed(&device.join("serial"))
+            .or_else(|| read_trimmed(&block.join("serial")))
+            .or_else(|| {
+                let page = fs::read(device.join("vpd_pg80")).ok()?;
+                let serial = String::from_utf8_lossy(page.get(4..)?).trim().to_string();
+                Some(serial).filter(|serial| !serial.is_empty())
+            })
+            .or_else(|| udev.get("ID_SERIAL_SHORT").cloned())
+            .unwrap_or_default();
+
+        if config.redact_serial {
+            storage.serial = redact_serial(&storage.serial);
+        }
+
+        // Transport from the device name and its path in sysfs
+        let path = fs::canonicalize(&block)
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        storage.transport = if VIRTUAL_PREFIXES.iter().any(|p| name.starts_with(p)) {
+            "virtual"
+        } else if name.starts_with("nvme") {
+            "NVMe"
+        } else if name.starts_with("vd") {
+            "virtio"
+        } else if name.starts_with("mmcblk") {
+            "MMC"
+        } else if name.starts_with("xvd") {
+            "Xen"
+        } else if path.contains("/usb") {
+            "USB"
+        } else if path.contains("/ata") {
+            "SATA"
+        } else if name.starts_with("sd") {
+            "SCSI"
+        } else {
+            "Unknown"
+        }
+        .to_string();
+
+        // Partition table from udev, either on the disk or on its partitions
+        // (/sys/block/sda/sda1), which udev tags with ID_PART_ENTRY_SCHEME, else
+        // from the disk itself
+        let partitions: Vec<_> = fs::read_dir(&block)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|entry| {
+                entry
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with(name.as_str())
+            })
+            .map(|entry| entry.path())
+            .collect();
+
+        storage.partition_table = udev
+            .get("ID_PART_TABLE_TYPE")
+            .cloned()
+            .or_else(|| {
+                partitions
+                    .iter()
+                    .find_map(|partition| read_udev(partition).remove("ID_PART_ENTRY_SCHEME"))
+            })
+            .or_else(|| {
+                let sector_size = read_trimmed(&block.join("queue/logical_block_size"))
+                    .and_then(|size| size.parse().ok())
+                    .filter(|&size| size >= 512)
+                    .unwrap_or(512);
+                read_partition_table(&name, sector_size).map(str::to_string)
+            })
+            .unwrap_or_else(|| {
+                let table = if partitions.is_empty() {
+                    "none"
+                } else {
+                    "Unknown"
+                };
+                table.to_string()
+            });
+
+        devices.push(storage);
+    }
+
+    devices
+}
+
+#[cfg(target_os = "windows")]
+fn get_storage(_config: &StorageConfig) -> Vec<StorageInfo> {
+    Vec::new()
+}
+
+pub fn load_storage_info(config: &StorageConfig, info: &mut SystemInfo) {
+    let devices = get_storage(config);
+
+    info.insert("storage_count".to_string(), devices.len().to_string());
+
+    for (i, device) in devices.iter().enumerate() {
+        let prefix = format!("storage_{}", i);
+        let kind = if ["virtio", "Xen", "virtual"].contains(&device.transport.as_str()) {
+            "Virtual"
+        } else if device.rotational {
+            "HDD"
+        } else {
+            "SSD"
+        };
+
+        info.insert(format!("{}_name", prefix), device.name.clone());
+        info.insert(format!("{}_model", prefix), device.model.clone());
+        info.insert(format!("{}_vendor", prefix), device.vendor.clone());
+        info.insert(format!("{}_serial", prefix), device.serial.clone());
+        info.insert(format!("{}_size", prefix), format_disk_size(device.size));
+        info.insert(format!("{}_type", prefix), kind.to_string());
+        info.insert(format!("{}_transport", prefix), device.transport.clone());
+        info.insert(
+            format!("{}_partition_table", prefix),
+            device.partition_table.clone(),
+        );
+    }
+
+    // Identical devices are grouped, e.g. "2x Samsung SSD 980 PRO 1TB NVMe". The size
+    // is left out when the model already has it, "500GB" covers 500.1GB
+    let mut groups: Vec<(String, usize)> = Vec::new();
+    for device in &devices {
+        let size = format_disk_size(device.size);
+        let whole: String = size
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .chain(size.chars().filter(|c| c.is_ascii_alphabetic()))
+            .collect();
+        let description = match device.model.replace(' ', "").contains(&whole) {
+            true => format!("{} {}", device.model, device.transport),
+            false => format!("{} {} {}", device.model, size, device.transport),
+        };
+
+        match groups.iter_mut().find(|(d, _)| *d == description) {
+            Some((_, count)) => *count += 1,
+            None => groups.push((description, 1)),
+        }
+    }
+
+    let summary: Vec<String> = groups
+        .iter()
+        .map(|(description, count)| match count {
+            1 => description.clone(),
+            _ => format!("{}x {}", count, description),
+        })
+        .collect();
+    info.insert("storage_summary".to_string(), summary.join(", "));
+}
@@ -0,0 +1,346 @@
+use std::env;
+
+use super::SystemInfo;
+
+// Maximum time to wait for a terminal to print its version, or tmux for its client
+#[cfg(target_os = "linux")]
+const VERSION_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(500);
+
+// Interpreters that script-based terminals run under
+#[cfg(target_os = "linux")]
+const INTERPRETERS: &[&str] = &["python", "perl"];
+
+struct TerminalInfo {
+    name: String,    // Terminal emulator (e.g. kitty, Konsole)
+    version: String, // Terminal emulator version
+    font: String,    // Terminal font (e.g. JetBrains Mono 11)
+}
+
+impl Default for TerminalInfo {
+    fn default() -> Self {
+        TerminalInfo {
+            name: "Unknown".to_string(),
+            version: "Unknown".to_string(),
+            font: "Unknown".to_string(),
+        }
+    }
+}
+
+// Known terminal emulators by process name
+#[cfg(target_os = "linux")]
+const TERMINALS: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "gnome-terminal-" => "GNOME Terminal",
+    "gnome-terminal" => "GNOME Terminal",
+    "kgx" => "GNOME Console",
+    "ptyxis-agent" => "Ptyxis",
+    "ptyxis" => "Ptyxis",
+    "konsole" => "Konsole",
+    "yakuake" => "Yakuake",
+    "kitty" => "kitty",
+    "alacritty" => "Alacritty",
+    "foot" => "foot",
+    "footclient" => "foot",
+    "wezterm-gui" => "WezTerm",
+    "ghostty" => "Ghostty",
+    "xterm" => "XTerm",
+    "urxvt" => "urxvt",
+    "urxvtd" => "urxvt",
+    "rxvt" => "rxvt",
+    "st" => "st",
+    "terminator" => "Terminator",
+    "tilix" => "Tilix",
+    "xfce4-terminal" => "Xfce Terminal",
+    "mate-terminal" => "MATE Terminal",
+    "lxterminal" => "LXTerminal",
+    "qterminal" => "QTerminal",
+    "sakura" => "Sakura",
+    "terminology" => "Terminology",
+    "guake" => "Guake",
+    "tilda" => "Tilda",
+    "contour" => "Contour",
+    "rio" => "Rio",
+    "blackbox" => "Black Box",
+    "cool-retro-term" => "cool-retro-term",
+    "tabby" => "Tabby",
+    "warp" => "Warp",
+    "code" => "VS Code",
+    "codium" => "VSCodium",
+};
+
+// Processes to look through when walking up to the terminal
+#[cfg(target_os = "linux")]
+const WRAPPERS: &[&str] = &[
+    "bash", "zsh", "fish", "sh", "dash", "ksh", "mksh", "tcsh", "csh", "nu", "elvish", "xonsh",
+    "ion", "oil", "osh", "pwsh", "sudo", "su", "doas", "script", "nohup", "time", "watch",
+    "kifetch", "tmux", "screen", "SCREEN", "zellij", "abduco", "dtach",
+];
+
+// Terminal emulators that announce themselves through environment variables
+fn get_terminal_from_env() -> Option<TerminalInfo> {
+    let var = |key: &str| env::var(key).ok().filter(|value| !value.is_empty());
+
+    let mut terminal = TerminalInfo::default();
+
+    if let Some(program) = var("TERM_PROGRAM").filter(|p| p != "tmux" && p != "screen") {
+        terminal.name = match program.as_str() {
+            "vscode" => "VS Code".to_string(),
+            "Apple_Terminal" => "Terminal.app".to_string(),
+            "iTerm.app" => "iTerm2".to_string(),
+            _ => program,
+        };
+        if let Some(version) = var("TERM_PROGRAM_VERSION") {
+            terminal.version = version;
+        }
+    } else if var("KITTY_WINDOW_ID").is_some() || var("KITTY_PID").is_some() {
+        terminal.name = "kitty".to_string();
+    } else if var("ALACRITTY_WINDOW_ID").is_some() || var("ALACRITTY_SOCKET").is_some() {
+        terminal.name = "Alacritty".to_string();
+    } else if var("WEZTERM_EXECUTABLE").is_some() || var("WEZTERM_PANE").is_some() {
+        terminal.name = "WezTerm".to_string();
+    } else if var("GHOSTTY_RESOURCES_DIR").is_some() {
+        terminal.name = "Ghostty".to_string();
+    } else if let Some(version) = var("KONSOLE_VERSION") {
+        // e.g. "230802" -> "23.08.2"
+        terminal.name = "Konsole".to_string();
+        let group = |range: std::ops::Range<usize>| version.get(range)?.parse::<u32>().ok();
+        if let (6, Some(major), Some(minor), Some(patch)) =
+            (version.len(), group(0..2), group(2..4), group(4..6))
+        {
+            terminal.version = format!("{}.{:02}.{}", major, minor, patch);
+        }
+    } else if var("TERMINATOR_UUID").is_some() {
+        terminal.name = "Terminator".to_string();
+    } else if var("TILIX_ID").is_some() {
+        terminal.name = "Tilix".to_string();
+    } else if var("WT_SESSION").is_some() {
+        terminal.name = "Windows Terminal".to_string();
+    } else if var("VTE_VERSION").is_some() {
+        terminal.name = "VTE-based terminal".to_string();
+    } else {
+        return None;
+    }
+
+    Some(terminal)
+}
+
+// Walk up the process tree, looking through shells, multiplexers and sudo
+#[cfg(target_os = "linux")]
+fn get_terminal_process() -> Option<(u32, String)> {
+    use crate::utils::{get_parent_pid, get_process_name, run_with_timeout};
+    use std::fs;
+
+    let mut pid = get_parent_pid(std::process::id())?;
+
+    while pid > 1 {
+        let name = get_process_name(pid)?;
+
+        if name == "tmux: server" {
+            // The server is detached, continue from the attached client instead
+            let output = run_with_timeout(
+                "tmux",
+                &["display-message", "-p", "#{client_pid}"],
+                VERSION_TIMEOUT,
+            )?;
+            pid = output.trim().parse().ok()?;
+            continue;
+        }
+
+        // Shell scripts show up under their own name, so check the executable too
+        let exe = fs::read_link(format!("/proc/{}/exe", pid))
+            .ok()
+            .and_then(|exe| exe.file_name().map(|n| n.to_string_lossy().to_string()))
+            .unwrap_or_default();
+
+        if name.starts_with("tmux")
+            || WRAPPERS.contains(&name.as_str())
+            || WRAPPERS.contains(&exe.as_str())
+        {
+            pid = get_parent_pid(pid)?;
+            continue;
+        }
+
+        return Some((pid, name));
+    }
+
+    None
+}
+
+// Ask the terminal for its version, e.g. "kitty 0.35.2 created by Kovid Goyal"
+#[cfg(target_os = "linux")]
+fn get_terminal_version(pid: u32, name: &str) -> Option<String> {
+    use crate::utils::{parse_version, run_with_timeout};
+    use std::fs;
+
+    let flag = match name {
+        "kitty" | "Alacritty" | "foot" | "WezTerm" | "Ghostty" | "Konsole" | "GNOME Terminal"
+        | "Xfce Terminal" | "Tilix" | "Terminator" | "MATE Terminal" | "QTerminal" => "--version",
+        "XTerm" | "st" => "-v",
+        _ => return None,
+    };
+
+    let exe = fs::read_link(format!("/proc/{}/exe", pid)).ok()?;
+    let exe_name = exe.file_name()?.to_string_lossy().to_string();
+
+    // Script terminals (e.g. Terminator) run under an interpreter, ask the script instead
+    let program = if INTERPRETERS.iter().any(|name| exe_name.starts_with(name)) {
+        let cmdline = fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+        cmdline
+            .split(|&byte| byte == 0)
+            .skip(1)
+            .map(|arg| String::from_utf8_lossy(arg).to_string())
+            .find(|arg| !arg.starts_with('-'))?
+    } else {
+        exe.to_string_lossy().to_string()
+    };
+
+    run_with_timeout(&program, &[flag], VERSION_TIMEOUT).and_then(|out| parse_version(&out))
+}
+
+// Read the configured font for terminals with a readable config file
+#[cfg(target_os = "linux")]
+fn get_terminal_font(name: &str) -> Option<String> {
+    use crate::utils::get_ini_value;
+    use std::fs;
+
+    let home = env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    let config_dir = env::var("XDG_CONFIG_HOME").unwrap_or_else(|_| format!("{}/.config", home));
+
+    let with_size = |family: String, size: Option<String>| match size {
+        Some(size) => format!("{} {}", family, size),
+        None => family,
+    };
+
+    match name {
+        "kitty" => {
+            // "font_family JetBrains Mono" and "font_size 11.0"
+            let config = fs::read_to_string(format!("{}/kitty/kitty.conf", config_dir)).ok()?;
+            let option = |key: &str| {
+                config.lines().find_map(|line| {
+                    let (name, value) = line.trim().split_once(char::is_whitespace)?;
+                    (name == key).then(|| value.trim().to_string())
+                })
+            };
+
+            let family = option("font_family").unwrap_or_else(|| "monospace".to_string());
+            Some(with_size(family, option("font_size")))
+        }
+        "Alacritty" => {
+            let config =
+                fs::read_to_string(format!("{}/alacritty/alacritty.toml", config_dir)).ok()?;
+            let config: toml::Value = toml::from_str(&config).ok()?;
+            let font = config.get("font")?;
+
+            let family = font
+                .get("normal")
+                .and_then(|normal| normal.get("family"))
+                .and_then(|family| family.as_str())
+                .unwrap_or("monospace")
+                .to_string();
+            let size = font.get("size").map(|size| match size {
+                toml::Value::Float(size) => size.to_string(),
+                toml::Value::Integer(size) => size.to_string(),
+                other => other.to_string(),
+            });
+
+            Some(with_size(family, size))
+        }
+        "foot" => {
+            // "font=JetBrainsMono:size=11", in [main] or before any section
+            let config = fs::read_to_string(format!("{}/foot/foot.ini", config_dir)).ok()?;
+            let font = get_ini_value(&config, "main", "font")
+                .or_else(|| get_ini_value(&config, "", "font"))?;
+
+            let mut parts = font.split(',').next()?.split(':');
+            let family = parts.next()?.trim().to_string();
+            let size = parts.find_map(|part| part.strip_prefix("size=").map(|s| s.to_string()));
+
+            Some(with_size(family, size))
+        }
+        "WezTerm" => {
+            // font = wezterm.font("JetBrains Mono"), font_size = 11.0
+            let config = [
+                format!("{}/wezterm/wezterm.lua", config_dir),
+                format!("{}/.wezterm.lua", home),
+            ]
+            .iter()
+            .find_map(|path| fs::read_to_string(path).ok())?;
+
+            let family = regex::Regex::new(
+                r#"wezterm\.font(?:_with_fallback)?\s*\(\s*\{?\s*['"]([^'"]+)['"]"#,
+            )
+            .ok()?
+            .captures(&config)
+            .map(|caps| caps[1].to_string())?;
+            let size = regex::Regex::new(r"font_size\s*=\s*([0-9.]+)")
+                .ok()?
+                .captures(&config)
+                .map(|caps| caps[1].to_string());
+
+            Some(with_size(family, size))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn get_terminal() -> TerminalInfo {
+    let mut terminal = TerminalInfo::default();
+
+    match get_terminal_process() {
+        Some((_, name)) if name == "sshd" || name.starts_with("sshd-") => {
+            // Remote session, only forwarded variables can tell the terminal
+            terminal = get_terminal_from_env().unwrap_or_default();
+            if terminal.name == "Unknown" {
+                terminal.name = "SSH".to_string();
+            }
+        }
+        Some((_, name)) if name == "login" || name == "agetty" || name == "getty" => {
+            terminal.name = "Linux console".to_string();
+        }
+        Some((pid, name)) if !name.starts_with("systemd") && name != "init" => {
+            terminal.name = TERMINALS
+                .get(name.as_str())
+                .map(|terminal| terminal.to_string())
+                .unwrap_or(name);
+
+            if let Some(version) = get_terminal_version(pid, &terminal.name) {
+                terminal.version = version;
+            }
+        }
+        _ => {
+            // Started from a service, an IDE or a detached multiplexer
+            terminal = get_terminal_from_env().unwrap_or_default();
+        }
+    }
+
+    // Some emulators only report their version through the environment
+    if terminal.version == "Unknown" {
+        if let Some(env_terminal) = get_terminal_from_env() {
+            if env_terminal.name == terminal.name {
+                terminal.version = env_terminal.version;
+            }
+        }
+    }
+
+    if let Some(font) = get_terminal_font(&terminal.name) {
+        terminal.font = font;
+    }
+
+    terminal
+}
+
+#[cfg(target_os = "windows")]
+fn get_terminal() -> TerminalInfo {
+    get_terminal_from_env().unwrap_or_else(|| TerminalInfo {
+        name: "Windows Console".to_string(),
+        ..Default::default()
+    })
+}
+
+pub fn load_terminal_info(info: &mut SystemInfo) {
+    let terminal = get_terminal();
+
+    info.insert("terminal_name".to_string(), terminal.name);
+    info.insert("terminal_version".to_string(), terminal.version);
+    info.insert("terminal_font".to_string(), terminal.font);
+}
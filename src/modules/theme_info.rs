@@ -0,0 +1,191 @@
+use super::SystemInfo;
+#[cfg(target_os = "linux")]
+use std::collections::HashMap;
+
+// Maximum time to wait for gsettings
+#[cfg(target_os = "linux")]
+const GSETTINGS_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(500);
+
+struct ThemeInfo {
+    gtk: String,    // GTK theme
+    qt: String,     // Qt widget style (and KDE color scheme)
+    icons: String,  // Icon theme
+    cursor: String, // Cursor theme
+    font: String,   // UI font
+}
+
+impl Default for ThemeInfo {
+    fn default() -> Self {
+        ThemeInfo {
+            gtk: "Unknown".to_string(),
+            qt: "Unknown".to_string(),
+            icons: "Unknown".to_string(),
+            cursor: "Unknown".to_string(),
+            font: "Unknown".to_string(),
+        }
+    }
+}
+
+// Qt fonts are stored as "Noto Sans,10,-1,5,50,0,0,0,0,0"
+#[cfg(target_os = "linux")]
+fn qt_font(font: &str) -> String {
+    let mut parts = font.split(',');
+    match (parts.next(), parts.next()) {
+        (Some(name), Some(size)) => format!("{} {}", name, size),
+        _ => font.to_string(),
+    }
+}
+
+// All keys of the GNOME interface settings in a single call, e.g. "gtk-theme" -> "Adwaita"
+#[cfg(target_os = "linux")]
+fn read_gsettings() -> HashMap<String, String> {
+    use crate::utils::run_with_timeout;
+
+    let schema = "org.gnome.desktop.interface";
+    let output = run_with_timeout(
+        "gsettings",
+        &["list-recursively", schema],
+        GSETTINGS_TIMEOUT,
+    )
+    .unwrap_or_default();
+
+    // "org.gnome.desktop.interface gtk-theme 'Adwaita'"
+    output
+        .lines()
+        .filter_map(|line| line.strip_prefix(schema)?.trim_start().split_once(' '))
+        .map(|(key, value)| (key.to_string(), value.trim().trim_matches('\'').to_string()))
+        .filter(|(_, value)| !value.is_empty())
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn get_theme() -> ThemeInfo {
+    use crate::utils::get_ini_value;
+    use std::cell::OnceCell;
+    use std::{env, fs};
+
+    let mut theme = ThemeInfo::default();
+
+    let home = env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    let config_dir = env::var("XDG_CONFIG_HOME").unwrap_or_else(|_| format!("{}/.config", home));
+    let read = |path: String| fs::read_to_string(path).unwrap_or_default();
+
+    // GTK 4, GTK 3 and GTK 2 settings, newest first
+    let gtk_settings = [
+        (
+            read(format!("{}/gtk-4.0/settings.ini", config_dir)),
+            "Settings",
+        ),
+        (
+            read(format!("{}/gtk-3.0/settings.ini", config_dir)),
+            "Settings",
+        ),
+        (read(format!("{}/.gtkrc-2.0", home)), ""),
+        (read(format!("{}/gtk-2.0/gtkrc", config_dir)), ""),
+    ];
+    let gtk = |key: &str| {
+        gtk_settings
+            .iter()
+            .find_map(|(content, section)| get_ini_value(content, section, key))
+    };
+
+    let kdeglobals = read(format!("{}/kdeglobals", config_dir));
+    let kde = |section: &str, key: &str| get_ini_value(&kdeglobals, section, key);
+
+    // qt6ct and qt5ct, used outside of KDE
+    let qtct = [
+        read(format!("{}/qt6ct/qt6ct.conf", config_dir)),
+        read(format!("{}/qt5ct/qt5ct.conf", config_dir)),
+    ];
+    let qt = |section: &str, key: &str| {
+        qtct.iter()
+            .find_map(|content| get_ini_value(content, section, key))
+    };
+
+    // GNOME keeps its settings in dconf and leaves settings.ini stale, so dconf
+    // wins there and is only a fallback on other desktops
+    let is_gnome = env::var("XDG_CURRENT_DESKTOP")
+        .map(|desktop| desktop.split(':').any(|name| name == "GNOME"))
+        .unwrap_or(false);
+    let dconf = OnceCell::new();
+    let gsettings = |key: &str| dconf.get_or_init(read_gsettings).get(key).cloned();
+    let gnome_first = |key: &str| if is_gnome { gsettings(key) } else { None };
+    let gnome_last = |key: &str| if is_gnome { None } else { gsettings(key) };
+
+    // GTK theme
+    if let Some(gtk_theme) = gnome_first("gtk-theme")
+        .or_else(|| gtk("gtk-theme-name"))
+        .or_else(|| gnome_last("gtk-theme"))
+    {
+        theme.gtk = gtk_theme;
+    }
+
+    // Qt style, e.g. "Breeze (BreezeDark)"
+    if let Some(style) = kde("KDE", "widgetStyle").or_else(|| qt("Appearance", "style")) {
+        theme.qt = match kde("General", "ColorScheme") {
+            Some(scheme) => format!("{} ({})", style, scheme),
+            None => style,
+        };
+    }
+
+    // Icon theme
+    if let Some(icons) = gnome_first("icon-theme")
+        .or_else(|| gtk("gtk-icon-theme-name"))
+        .or_else(|| kde("Icons", "Theme"))
+        .or_else(|| qt("Appearance", "icon_theme"))
+        .or_else(|| gnome_last("icon-theme"))
+    {
+        theme.icons = icons;
+    }
+
+    // Cursor theme, the default theme inherits from the selected one
+    let cursor_index = [
+        read(format!("{}/.icons/default/index.theme", home)),
+        read(format!("{}/.local/share/icons/default/index.theme", home)),
+        read("/usr/share/icons/default/index.theme".to_string()),
+    ];
+    let kcminputrc = read(format!("{}/kcminputrc", config_dir));
+
+    if let Some(cursor) = env::var("XCURSOR_THEME")
+        .ok()
+        .filter(|cursor| !cursor.is_empty())
+        .or_else(|| gnome_first("cursor-theme"))
+        .or_else(|| gtk("gtk-cursor-theme-name"))
+        .or_else(|| get_ini_value(&kcminputrc, "Mouse", "cursorTheme"))
+        .or_else(|| gnome_last("cursor-theme"))
+        .or_else(|| {
+            cursor_index
+                .iter()
+                .find_map(|content| get_ini_value(content, "Icon Theme", "Inherits"))
+        })
+    {
+        theme.cursor = cursor;
+    }
+
+    // UI font, e.g. "Cantarell 11"
+    if let Some(font) = gnome_first("font-name")
+        .or_else(|| gtk("gtk-font-name"))
+        .or_else(|| kde("General", "font").map(|font| qt_font(&font)))
+        .or_else(|| qt("Fonts", "general").map(|font| qt_font(&font)))
+        .or_else(|| gnome_last("font-name"))
+    {
+        theme.font = font;
+    }
+
+    theme
+}
+
+#[cfg(target_os = "windows")]
+fn get_theme() -> ThemeInfo {
+    ThemeInfo::default()
+}
+
+pub fn load_theme_info(info: &mut SystemInfo) {
+    let theme = get_theme();
+
+    info.insert("theme_gtk".to_string(), theme.gtk);
+    info.insert("theme_qt".to_string(), theme.qt);
+    info.insert("theme_icons".to_string(), theme.icons);
+    info.insert("theme_cursor".to_string(), theme.cursor);
+    info.insert("theme_font".to_string(), theme.font);
+}
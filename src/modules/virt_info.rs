@@ -0,0 +1,295 @@
+use super::SystemInfo;
+
+struct VirtInfo {
+    hypervisor: Option<String>, // Hypervisor vendor (e.g. KVM, VMware)
+    container: Option<String>,  // Container runtime (e.g. Docker, LXC)
+}
+
+// Values of the "container" variable set by container managers for PID 1
+#[cfg(target_os = "linux")]
+const CONTAINER_NAMES: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "docker" => "Docker",
+    "podman" => "Podman",
+    "lxc" => "LXC",
+    "lxc-libvirt" => "LXC",
+    "systemd-nspawn" => "systemd-nspawn",
+    "rkt" => "rkt",
+    "oci" => "OCI",
+    "wsl" => "WSL",
+    "proot" => "proot",
+    "pouch" => "Pouch",
+};
+
+#[cfg(target_os = "linux")]
+pub fn get_container() -> Option<String> {
+    use std::env;
+    use std::fs;
+    use std::path::Path;
+
+    // Kubernetes injects the API server address into every pod
+    if env::var("KUBERNETES_SERVICE_HOST").is_ok()
+        || Path::new("/var/run/secrets/kubernetes.io").exists()
+    {
+        return Some("Kubernetes".to_string());
+    }
+
+    if Path::new("/.dockerenv").exists() {
+        return Some("Docker".to_string());
+    }
+
+    if Path::new("/run/.containerenv").exists() {
+        return Some("Podman".to_string());
+    }
+
+    // systemd copies PID 1's "container" variable here, readable without root
+    let container = fs::read_to_string("/run/systemd/container")
+        .ok()
+        .map(|name| name.trim().to_string())
+        .or_else(|| {
+            let environ = fs::read("/proc/1/environ").ok()?;
+            environ
+                .split(|&b| b == 0)
+                .find_map(|var| var.strip_prefix(b"container="))
+                .map(|name| String::from_utf8_lossy(name).to_string())
+        });
+
+    if let Some(name) = container.filter(|name| !name.is_empty()) {
+        return Some(
+            CONTAINER_NAMES
+                .get(name.as_str())
+                .map(|name| name.to_string())
+                .unwrap_or(name),
+        );
+    }
+
+    if let Ok(cgroup) = fs::read_to_string("/proc/1/cgroup") {
+        if cgroup.contains("/kubepods") {
+            return Some("Kubernetes".to_string());
+        } else if cgroup.contains("/docker") || cgroup.contains("docker-") {
+            return Some("Docker".to_string());
+        } else if cgroup.contains("libpod") {
+            return Some("Podman".to_string());
+        } else if cgroup.contains("/lxc") {
+            return Some("LXC".to_string());
+        }
+    }
+
+    // OpenVZ exposes /proc/vz in containers and /proc/bc on the host
+    if Path::new("/proc/vz").exists() && !Path::new("/proc/bc").exists() {
+        return Some("OpenVZ".to_string());
+    }
+
+    // WSL kernels are named like "5.15.153.1-microsoft-standard-WSL2"
+    if let Ok(release) = fs::read_to_string("/proc/sys/kernel/osrelease") {
+        if release.contains("WSL2") {
+            return Some("WSL2".to_string());
+        } else if release.contains("Microsoft") {
+            return Some("WSL".to_string());
+        }
+    }
+
+    None
+}
+
+#[cfg(target_os = "windows")]
+pub fn get_container() -> Option<String> {
+    // Windows containers run a dedicated "CExecSvc" service
+    use winreg::{enums::HKEY_LOCAL_MACHINE, RegKey};
+
+    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    hklm.open_subkey("SYSTEM\\CurrentControlSet\\Services\\cexecsvc")
+        .ok()
+        .map(|_| "Windows Container".to_string())
+}
+
+// Hypervisor vendors by CPUID leaf 0x40000000 signature
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+const CPUID_VENDORS: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "KVMKVMKVM" => "KVM",
+    "Microsoft Hv" => "Hyper-V",
+    "VMwareVMware" => "VMware",
+    "XenVMMXenVMM" => "Xen",
+    "VBoxVBoxVBox" => "VirtualBox",
+    "TCGTCGTCGTCG" => "QEMU",
+    " lrpepyh  vr" => "Parallels",
+    "bhyve bhyve " => "bhyve",
+    "ACRNACRNACRN" => "ACRN",
+    "QNXQVMBSQG" => "QNX",
+    "Jailhouse" => "Jailhouse",
+    "SRESRESRESRE" => "SRE",
+    "UnisysSpar64" => "Unisys s-Par",
+};
+
+// Hypervisor vendors by DMI vendor and product strings, checked in order.
+// Vendors that also sell hardware (Oracle, Amazon) are not enough on their own
+const DMI_VENDORS: &[(&str, &str)] = &[
+    ("QEMU", "QEMU"),
+    ("KVM", "KVM"),
+    ("VMware", "VMware"),
+    ("VMW", "VMware"),
+    ("innotek GmbH", "VirtualBox"),
+    ("VirtualBox", "VirtualBox"),
+    ("Xen", "Xen"),
+    ("Bochs", "Bochs"),
+    ("Parallels", "Parallels"),
+    ("BHYVE", "bhyve"),
+    ("Apple Virtualization", "Apple Virtualization"),
+];
+
+// The hypervisor signature reported by CPUID, only present inside a guest
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[allow(unused_unsafe)] // __cpuid is a safe function on newer toolchains
+fn get_cpuid_hypervisor() -> Option<String> {
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::__cpuid;
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::__cpuid;
+
+    // Bit 31 of ECX in leaf 1 is the hypervisor present bit
+    let features = unsafe { __cpuid(1) };
+    if features.ecx & (1 << 31) == 0 {
+        return None;
+    }
+
+    let leaf = unsafe { __cpuid(0x4000_0000) };
+    let bytes: Vec<u8> = [leaf.ebx, leaf.ecx, leaf.edx]
+        .iter()
+        .flat_map(|reg| reg.to_le_bytes())
+        .collect();
+    let signature = String::from_utf8_lossy(&bytes)
+        .trim_end_matches('\0')
+        .to_string();
+
+    Some(
+        CPUID_VENDORS
+            .get(signature.as_str())
+            .map(|vendor| vendor.to_string())
+            .unwrap_or_else(|| "Unknown".to_string()),
+    )
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+fn get_cpuid_hypervisor() -> Option<String> {
+    None
+}
+
+// DMI strings are the system vendor, product name, BIOS vendor and board vendor
+fn get_dmi_hypervisor(strings: &[String]) -> Option<String> {
+    let product = strings.get(1).map(|s| s.as_str()).unwrap_or_default();
+
+    // Hyper-V guests report Microsoft as the vendor and "Virtual Machine" as the product
+    if strings.iter().any(|s| s.contains("Microsoft")) && product == "Virtual Machine" {
+        return Some("Hyper-V".to_string());
+    }
+
+    // Cloud instances run on KVM-based hypervisors, except bare metal ones (e.g. "m5.metal")
+    if strings
+        .iter()
+        .any(|s| s == "Amazon EC2" || s == "Google Compute Engine")
+    {
+        return Some("KVM".to_string()).filter(|_| !product.ends_with(".metal"));
+    }
+
+    DMI_VENDORS
+        .iter()
+        .find(|(name, _)| strings.iter().any(|s| s.contains(name)))
+        .map(|(_, vendor)| vendor.to_string())
+}
+
+// Hypervisor the system runs under, None on bare metal
+#[cfg(target_os = "linux")]
+pub fn get_hypervisor() -> Option<String> {
+    use std::fs;
+
+    let dmi: Vec<String> = ["sys_vendor", "product_name", "bios_vendor", "board_vendor"]
+        .iter()
+        .map(|file| fs::read_to_string(format!("/sys/class/dmi/id/{}", file)).unwrap_or_default())
+        .map(|value| value.trim().to_string())
+        .collect();
+
+    if let Some(vendor) = get_dmi_hypervisor(&dmi) {
+        return Some(vendor);
+    }
+
+    // Xen guests without DMI (paravirtualized)
+    if let Ok(kind) = fs::read_to_string("/sys/hypervisor/type") {
+        if kind.trim() == "xen" {
+            return Some("Xen".to_string());
+        }
+    }
+
+    // QEMU's generic ARM machine
+    if fs::read_to_string("/sys/firmware/devicetree/base/compatible")
+        .map(|compatible| compatible.contains("linux,dummy-virt"))
+        .unwrap_or(false)
+    {
+        return Some("QEMU".to_string());
+    }
+
+    get_cpuid_hypervisor()
+}
+
+// Hypervisor the system runs under, None on bare metal
+#[cfg(target_os = "windows")]
+pub fn get_hypervisor() -> Option<String> {
+    use winreg::{enums::HKEY_LOCAL_MACHINE, RegKey};
+
+    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    let dmi: Vec<String> = hklm
+        .open_subkey("HARDWARE\\DESCRIPTION\\System\\BIOS")
+        .map(|key| {
+            [
+                "SystemManufacturer",
+                "SystemProductName",
+                "BIOSVendor",
+                "BaseBoardManufacturer",
+            ]
+            .iter()
+            .map(|name| key.get_value::<String, _>(name).unwrap_or_default())
+            .collect()
+        })
+        .unwrap_or_default();
+
+    if let Some(vendor) = get_dmi_hypervisor(&dmi) {
+        return Some(vendor);
+    }
+
+    // With virtualization-based security the host itself runs under Hyper-V
+    get_cpuid_hypervisor().filter(|vendor| vendor != "Hyper-V")
+}
+
+fn get_virt() -> VirtInfo {
+    VirtInfo {
+        hypervisor: get_hypervisor(),
+        container: get_container(),
+    }
+}
+
+pub fn load_virt_info(info: &mut SystemInfo) {
+    let virt = get_virt();
+
+    // Like systemd-detect-virt, a container takes precedence over the VM it runs in
+    let kind = match (&virt.container, &virt.hypervisor) {
+        (Some(_), _) => "container",
+        (None, Some(_)) => "vm",
+        (None, None) => "none",
+    };
+
+    let summary = match (&virt.container, &virt.hypervisor) {
+        (Some(container), Some(hypervisor)) => format!("{} on {}", container, hypervisor),
+        (Some(container), None) => container.clone(),
+        (None, Some(hypervisor)) => hypervisor.clone(),
+        (None, None) => "None".to_string(),
+    };
+
+    info.insert("virt_type".to_string(), kind.to_string());
+    info.insert(
+        "virt_hypervisor".to_string(),
+        virt.hypervisor.unwrap_or_else(|| "None".to_string()),
+    );
+    info.insert(
+        "virt_container".to_string(),
+        virt.container.unwrap_or_else(|| "None".to_string()),
+    );
+    info.insert("virt_summary".to_string(), summary);
+}
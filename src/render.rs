@@ -41,7 +41,7 @@ pub fn render_output(
     let padding = " ".repeat(config.general.padding);
 
     // Process each line of the layout
-    for (_, line_template) in config.layout.lines.iter().enumerate() {
+    for line_template in config.layout.lines.iter() {
         // Get the corresponding logo line, or an empty line if there are no more
         let logo_line = if let Some(line) = logo_iter.next() {
             line
@@ -76,6 +76,16 @@ fn render_template(template: &str, config: &Config, system_info: &SystemInfo) ->
 
             // Check if it's a color variable
             if let Some(color_value) = config.colors.get(var_name) {
+                // "os" is the distribution's accent color from os-release ANSI_COLOR,
+                // bright red when there is none (e.g. on Windows)
+                if color_value == "os" {
+                    return system_info
+                        .get("os_color")
+                        .filter(|color| !color.is_empty())
+                        .cloned()
+                        .unwrap_or_else(|| COLOR_MAP["bright_red"].to_string());
+                }
+
                 if let Some(ansi_code) = COLOR_MAP.get(color_value.as_str()) {
                     return ansi_code.to_string();
                 }
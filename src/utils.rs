@@ -1,3 +1,8 @@
+use std::collections::HashMap;
+use std::process::{Command, Output, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
 use crate::modules::SystemInfo;
 
 pub struct Size {
@@ -51,6 +56,19 @@ pub fn get_size(bytes: f64) -> Size {
     }
 }
 
+impl Size {
+    // Value expressed in another metric, e.g. used memory in the metric of the total
+    pub fn value_in(&self, metric: &str) -> f64 {
+        match metric {
+            "TB" => self.tb,
+            "GB" => self.gb,
+            "MB" => self.mb,
+            "KB" => self.kb,
+            _ => self.bytes,
+        }
+    }
+}
+
 pub fn insert_size(
     prefix: &str,
     free_bytes: usize,
@@ -68,14 +86,14 @@ pub fn insert_size(
     };
     let metric = total.metric;
 
-    info.insert(format!("{}_metric", prefix), metric);
+    // Free and used share the metric of the total, so "512.00/2.00 GB" can't happen
     info.insert(
         format!("{}_free", prefix),
-        format!("{:.2}", free.metric_value),
+        format!("{:.2}", free.value_in(&metric)),
     );
     info.insert(
         format!("{}_used", prefix),
-        format!("{:.2}", used.metric_value),
+        format!("{:.2}", used.value_in(&metric)),
     );
     info.insert(
         format!("{}_total", prefix),
@@ -85,4 +103,200 @@ pub fn insert_size(
         format!("{}_percentage", prefix),
         format!("{:.2}", percentage),
     );
+    info.insert(format!("{}_metric", prefix), metric);
+}
+
+// Read a value from an INI-style file (GTK settings.ini, kdeglobals, qt5ct.conf, ...)
+// An empty section matches keys before the first section header (e.g. .gtkrc-2.0)
+pub fn get_ini_value(content: &str, section: &str, key: &str) -> Option<String> {
+    let mut current = String::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+
+        if line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        if line.starts_with('[') && line.ends_with(']') {
+            current = line[1..line.len() - 1].to_string();
+            continue;
+        }
+
+        if current != section {
+            continue;
+        }
+
+        if let Some((name, value)) = line.split_once('=') {
+            if name.trim() == key {
+                let value = value.trim().trim_matches('"').trim();
+                if !value.is_empty() {
+                    return Some(value.to_string());
+                }
+            }
+        }
+    }
+
+    None
+}
+
+// First token that looks like a version, e.g. "GNOME Shell 46.0" -> "46.0",
+// "XTerm(390)" -> "390", "st-0.9" -> "0.9"
+pub fn parse_version(output: &str) -> Option<String> {
+    output
+        .split(|c: char| c.is_whitespace() || c == '(' || c == ')' || c == ',')
+        .filter_map(|token| {
+            let token = token.trim_start_matches('v');
+            if token.starts_with(|c: char| c.is_ascii_digit()) {
+                Some(token)
+            } else {
+                token.rsplit_once('-').map(|(_, version)| version)
+            }
+        })
+        .find(|token| token.starts_with(|c: char| c.is_ascii_digit()))
+        .map(|token| token.trim_end_matches(['.', ':', ';']).to_string())
+}
+
+// Parent process id from /proc/<pid>/stat
+#[cfg(target_os = "linux")]
+pub fn get_parent_pid(pid: u32) -> Option<u32> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+
+    // The name is in parentheses and may contain spaces: "1234 (tmux: client) S 1200 ..."
+    let (_, fields) = stat.rsplit_once(')')?;
+    fields.split_whitespace().nth(1)?.parse().ok()
+}
+
+// Process name from /proc/<pid>/comm (truncated to 15 characters by the kernel)
+#[cfg(target_os = "linux")]
+pub fn get_process_name(pid: u32) -> Option<String> {
+    std::fs::read_to_string(format!("/proc/{}/comm", pid))
+        .ok()
+        .map(|comm| comm.trim().to_string())
+}
+
+// Run a command and return its output, killing it if it takes longer than the timeout
+pub fn run_output_with_timeout(program: &str, args: &[&str], timeout: Duration) -> Option<Output> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .ok()?;
+
+    let start = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if start.elapsed() < timeout => thread::sleep(Duration::from_millis(5)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    }
+
+    child.wait_with_output().ok()
+}
+
+// Run a command and return its stdout, killing it if it takes longer than the timeout
+pub fn run_with_timeout(program: &str, args: &[&str], timeout: Duration) -> Option<String> {
+    let output = run_output_with_timeout(program, args, timeout)?;
+    let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+
+    // Some programs print their version to stderr
+    if text.trim().is_empty() {
+        text = String::from_utf8_lossy(&output.stderr).to_string();
+    }
+
+    Some(text)
+}
+
+// Placeholders firmware leaves in empty or unknown SMBIOS fields,
+// e.g. "To Be Filled By O.E.M." or a serial of zeros
+pub fn is_placeholder(value: &str) -> bool {
+    let lower = value.trim().to_lowercase();
+    [
+        "default string",
+        "system product name",
+        "system manufacturer",
+        "system version",
+        "not applicable",
+        "type1productconfigid",
+        "o.e.m.",
+        "none",
+        "invalid",
+        "x.x",
+        "0123456789",
+        "123456789",
+    ]
+    .contains(&lower.as_str())
+        || lower.chars().all(|c| c == '0' || c == ' ')
+        || lower.contains("unknown")
+        || lower.contains("not specified")
+        || lower.contains("to be filled")
+        || lower.starts_with("manufacturer")
+        || lower.starts_with("partnum")
+}
+
+// Parse os-release style KEY=value files (/etc/os-release, /etc/lsb-release, ...)
+pub fn parse_key_values(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            let value = value.trim().trim_matches('"').trim_matches('\'');
+            (key.trim().to_string(), value.to_string())
+        })
+        .collect()
+}
+
+// Fields of /etc/os-release, falling back to /usr/lib/os-release and /etc/lsb-release
+#[cfg(target_os = "linux")]
+pub fn read_os_release() -> HashMap<String, String> {
+    if let Some(content) = ["/etc/os-release", "/usr/lib/os-release"]
+        .iter()
+        .find_map(|path| std::fs::read_to_string(path).ok())
+    {
+        return parse_key_values(&content);
+    }
+
+    // DISTRIB_ID=Ubuntu, DISTRIB_RELEASE=22.04, ... mapped to their os-release names
+    let lsb = std::fs::read_to_string("/etc/lsb-release")
+        .map(|content| parse_key_values(&content))
+        .unwrap_or_default();
+
+    [
+        ("DISTRIB_ID", "ID"),
+        ("DISTRIB_ID", "NAME"),
+        ("DISTRIB_RELEASE", "VERSION_ID"),
+        ("DISTRIB_CODENAME", "VERSION_CODENAME"),
+        ("DISTRIB_DESCRIPTION", "PRETTY_NAME"),
+    ]
+    .iter()
+    .filter_map(|(lsb_key, key)| {
+        let value = lsb.get(*lsb_key)?;
+        let value = match *key {
+            "ID" => value.to_lowercase(),
+            _ => value.clone(),
+        };
+        Some((key.to_string(), value))
+    })
+    .collect()
+}
+
+// Expand a leading "~" to the home directory
+pub fn expand_home(path: &str) -> String {
+    match path.strip_prefix('~') {
+        Some(rest) => {
+            let home = std::env::var("HOME")
+                .or_else(|_| std::env::var("USERPROFILE"))
+                .unwrap_or_else(|_| ".".to_string());
+            format!("{}{}", home, rest)
+        }
+        None => path.to_string(),
+    }
 }